@@ -0,0 +1,323 @@
+use crate::constants::SENTINEL_ADDRESS;
+use crate::encoding::encode_function_call;
+use crate::safe::SafeTransactionBuilder;
+use crate::transaction::RawCall;
+use ethers::abi::Token;
+use ethers::types::{Address, H256, U256};
+use safe_client_gateway::common::models::data_decoded::Operation;
+use tracing::warn;
+
+/// The owners and threshold of a safe
+///
+/// Owners are held as parsed addresses, so comparisons ignore the casing the service used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafePolicy {
+    pub owners: Vec<Address>,
+    pub threshold: u32,
+}
+
+impl SafePolicy {
+    pub fn is_owner(&self, address: Address) -> bool {
+        self.owners.contains(&address)
+    }
+
+    /// How many more signatures are needed to reach the threshold
+    pub fn remaining_signatures(&self, collected: usize) -> usize {
+        (self.threshold as usize).saturating_sub(collected)
+    }
+
+    pub fn can_execute(&self, collected: usize) -> bool {
+        self.remaining_signatures(collected) == 0
+    }
+}
+
+/// Canonical fallback handlers, deployed at the same address on every chain with the release
+/// except where noted
+pub const KNOWN_FALLBACK_HANDLERS: &[(&str, FallbackHandlerKind)] = &[
+    (
+        "0xd5D82B6aDDc9027B22dCA772Aa68D5d74cdBdF44",
+        FallbackHandlerKind::Default,
+    ),
+    (
+        "0xf48f2B2d2a534e402487b3ee7C18c33Aec0Fe5e4",
+        FallbackHandlerKind::Compatibility,
+    ),
+    (
+        "0x017062a1dE2FE6b99BE3d9d37841FeD19F573804",
+        FallbackHandlerKind::Compatibility,
+    ),
+    // zkSync Era only, its CREATE2 addresses differ from every other chain's
+    (
+        "0x2f870a80647BbC554F3a0EBD093f11B4d2a7492A",
+        FallbackHandlerKind::Compatibility,
+    ),
+    (
+        "0xfd0732Dc9E303f09fCEf3a7388Ad10A83459Ec99",
+        FallbackHandlerKind::Compatibility,
+    ),
+];
+
+/// What a safe's fallback handler is, by its address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackHandlerKind {
+    None,
+    /// The DefaultCallbackHandler of safes before v1.3.0, only receives tokens
+    Default,
+    /// A CompatibilityFallbackHandler, which also implements EIP-1271 `isValidSignature`
+    Compatibility,
+    Unknown,
+}
+
+impl FallbackHandlerKind {
+    pub fn of(handler: Option<Address>) -> Self {
+        match handler {
+            None => Self::None,
+            Some(handler) if handler.is_zero() => Self::None,
+            Some(handler) => KNOWN_FALLBACK_HANDLERS
+                .iter()
+                .find(|(address, _)| address.parse::<Address>().ok() == Some(handler))
+                .map_or(Self::Unknown, |(_, kind)| *kind),
+        }
+    }
+
+    /// Unknown handlers may support it too, but that can only be told by calling them
+    pub fn supports_eip1271(&self) -> bool {
+        *self == Self::Compatibility
+    }
+}
+
+/// Owners and modules are stored as linked lists starting at the sentinel address,
+/// removing an item requires a pointer to the one before it
+///
+/// `list` must be in contract order, as returned by the service or `getOwners`
+pub fn prev_in_list(list: &[Address], item: Address) -> anyhow::Result<Address> {
+    match list.iter().position(|entry| *entry == item) {
+        Some(0) => Ok(*SENTINEL_ADDRESS),
+        Some(index) => Ok(list[index - 1]),
+        None => anyhow::bail!("{item:?} is not in the list"),
+    }
+}
+
+/// Transactions the safe makes to itself to manage its own configuration
+impl SafeTransactionBuilder<RawCall> {
+    fn self_call(chain_id: u64, safe_address: Address, data: Vec<u8>) -> Self {
+        SafeTransactionBuilder::new(
+            RawCall {
+                to: safe_address,
+                value: U256::zero(),
+                data,
+            },
+            chain_id,
+            safe_address,
+        )
+        .operation(Operation::CALL)
+    }
+
+    pub fn add_owner(
+        chain_id: u64,
+        safe_address: Address,
+        new_owner: Address,
+        new_threshold: u32,
+    ) -> Self {
+        Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call(
+                "addOwnerWithThreshold(address,uint256)",
+                &[
+                    Token::Address(new_owner),
+                    Token::Uint(U256::from(new_threshold)),
+                ],
+            ),
+        )
+    }
+
+    /// Fetches the current owners to find the `prevOwner` pointer
+    pub async fn remove_owner(
+        chain_id: u64,
+        safe_address: Address,
+        owner: Address,
+        new_threshold: u32,
+    ) -> anyhow::Result<Self> {
+        let owners = crate::api::policy(chain_id, safe_address).await?.owners;
+        let prev_owner = prev_in_list(&owners, owner)?;
+
+        Ok(Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call(
+                "removeOwner(address,address,uint256)",
+                &[
+                    Token::Address(prev_owner),
+                    Token::Address(owner),
+                    Token::Uint(U256::from(new_threshold)),
+                ],
+            ),
+        ))
+    }
+
+    /// `owners` must be the current owners in contract order
+    pub fn swap_owner(
+        chain_id: u64,
+        safe_address: Address,
+        old_owner: Address,
+        new_owner: Address,
+        owners: &[Address],
+    ) -> anyhow::Result<Self> {
+        let prev_owner = prev_in_list(owners, old_owner)?;
+
+        Ok(Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call(
+                "swapOwner(address,address,address)",
+                &[
+                    Token::Address(prev_owner),
+                    Token::Address(old_owner),
+                    Token::Address(new_owner),
+                ],
+            ),
+        ))
+    }
+
+    pub fn change_threshold(chain_id: u64, safe_address: Address, threshold: u32) -> Self {
+        Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call(
+                "changeThreshold(uint256)",
+                &[Token::Uint(U256::from(threshold))],
+            ),
+        )
+    }
+
+    pub fn enable_module(chain_id: u64, safe_address: Address, module: Address) -> Self {
+        Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call("enableModule(address)", &[Token::Address(module)]),
+        )
+    }
+
+    /// Errors on the zero address, use [Self::remove_fallback_handler] to remove it on purpose
+    pub fn set_fallback_handler(
+        chain_id: u64,
+        safe_address: Address,
+        handler: Address,
+    ) -> anyhow::Result<Self> {
+        if handler.is_zero() {
+            anyhow::bail!("setting the zero address fallback handler would remove it");
+        }
+        Ok(Self::fallback_handler_call(chain_id, safe_address, handler))
+    }
+
+    pub fn remove_fallback_handler(chain_id: u64, safe_address: Address) -> Self {
+        Self::fallback_handler_call(chain_id, safe_address, Address::zero())
+    }
+
+    fn fallback_handler_call(chain_id: u64, safe_address: Address, handler: Address) -> Self {
+        Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call("setFallbackHandler(address)", &[Token::Address(handler)]),
+        )
+    }
+
+    /// The zero address removes the guard
+    ///
+    /// A guard checks every transaction, one that reverts unconditionally locks the safe for good
+    pub fn set_guard(chain_id: u64, safe_address: Address, guard: Address) -> Self {
+        if !guard.is_zero() {
+            warn!(
+                "setting guard {:?} on safe {:?}, a faulty guard can permanently lock the safe",
+                guard, safe_address
+            );
+        }
+        Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call("setGuard(address)", &[Token::Address(guard)]),
+        )
+    }
+
+    /// Pre-approves `safe_tx_hash` on `target_safe` from `safe_address`, for a safe owning another
+    ///
+    /// Once executed, `safe_address` is counted with [crate::signature::approved_hash_signature]
+    pub fn approve_hash(
+        chain_id: u64,
+        safe_address: Address,
+        target_safe: Address,
+        safe_tx_hash: H256,
+    ) -> Self {
+        SafeTransactionBuilder::new(
+            RawCall {
+                to: target_safe,
+                value: U256::zero(),
+                data: encode_function_call(
+                    "approveHash(bytes32)",
+                    &[Token::FixedBytes(safe_tx_hash.as_bytes().to_vec())],
+                ),
+            },
+            chain_id,
+            safe_address,
+        )
+    }
+
+    /// Fetches the enabled modules to find the `prevModule` pointer
+    pub async fn disable_module(
+        chain_id: u64,
+        safe_address: Address,
+        module: Address,
+    ) -> anyhow::Result<Self> {
+        let modules = crate::api::modules(chain_id, safe_address).await?;
+        let prev_module = prev_in_list(&modules, module)?;
+
+        Ok(Self::self_call(
+            chain_id,
+            safe_address,
+            encode_function_call(
+                "disableModule(address,address)",
+                &[Token::Address(prev_module), Token::Address(module)],
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_prev_in_list() {
+    let owners: Vec<Address> = [
+        "0x000000000000000000000000000000000000000a",
+        "0x000000000000000000000000000000000000000b",
+        "0x000000000000000000000000000000000000000c",
+    ]
+    .iter()
+    .map(|owner| owner.parse().unwrap())
+    .collect();
+
+    assert_eq!(prev_in_list(&owners, owners[0]).unwrap(), *SENTINEL_ADDRESS);
+    assert_eq!(prev_in_list(&owners, owners[1]).unwrap(), owners[0]);
+    assert_eq!(prev_in_list(&owners, owners[2]).unwrap(), owners[1]);
+    assert!(prev_in_list(&owners, Address::zero()).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_fallback_handler_kind() {
+    let compatibility: Address = "0xf48f2B2d2a534e402487b3ee7C18c33Aec0Fe5e4"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        FallbackHandlerKind::of(Some(compatibility)),
+        FallbackHandlerKind::Compatibility
+    );
+    assert!(FallbackHandlerKind::of(Some(compatibility)).supports_eip1271());
+    assert_eq!(
+        FallbackHandlerKind::of(Some(Address::zero())),
+        FallbackHandlerKind::None
+    );
+    assert_eq!(
+        FallbackHandlerKind::of(Some(Address::repeat_byte(1))),
+        FallbackHandlerKind::Unknown
+    );
+}
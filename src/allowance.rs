@@ -0,0 +1,128 @@
+use crate::chain::Chain;
+use crate::constants::{ALLOWANCE_MODULE_ADDRESS, ALLOWANCE_MODULE_V0_1_1_ADDRESS};
+use crate::encoding::encode_function_call;
+use ethers::abi::Token;
+use ethers::prelude::abigen;
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use std::sync::Arc;
+
+abigen!(
+    AllowanceModule,
+    r#"[
+        function getTokenAllowance(address safe, address delegate, address token) external view returns (uint256[5])
+    ]"#,
+);
+
+/// A delegate's spending limit on one token, as stored by the AllowanceModule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allowance {
+    pub amount: U256,
+    pub spent: U256,
+    /// Minutes between resets of `spent`, 0 for a one-off allowance
+    pub reset_min: u64,
+    /// Minutes since the unix epoch
+    pub last_reset: u64,
+    /// Bumped on every transfer, part of the hash the delegate signs
+    pub nonce: u64,
+}
+
+impl Allowance {
+    pub fn remaining(&self) -> U256 {
+        self.amount.saturating_sub(self.spent)
+    }
+}
+
+/// The AllowanceModule deployment of a chain, None where it isn't known
+pub fn default_module(chain_id: u64) -> Option<Address> {
+    match Chain::from_u64(chain_id) {
+        Chain::Base | Chain::Sepolia => Some(*ALLOWANCE_MODULE_V0_1_1_ADDRESS),
+        Chain::Other(_) => None,
+        _ => Some(*ALLOWANCE_MODULE_ADDRESS),
+    }
+}
+
+/// Calldata of `executeAllowanceTransfer` without a payment, `signature` may be empty when the
+/// delegate is the sender
+pub fn transfer_calldata(
+    safe: Address,
+    token: Address,
+    to: Address,
+    amount: U256,
+    delegate: Address,
+    signature: Vec<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    if amount.bits() > 96 {
+        anyhow::bail!("allowance transfer amount {amount} does not fit in a uint96");
+    }
+    Ok(encode_function_call(
+        "executeAllowanceTransfer(address,address,address,uint96,address,uint96,address,bytes)",
+        &[
+            Token::Address(safe),
+            Token::Address(token),
+            Token::Address(to),
+            Token::Uint(amount),
+            Token::Address(Address::zero()),
+            Token::Uint(U256::zero()),
+            Token::Address(delegate),
+            Token::Bytes(signature),
+        ],
+    ))
+}
+
+/// Reads `getTokenAllowance` on `module`, or the chain's default module if None
+pub async fn allowance<M: Middleware + 'static>(
+    chain_id: u64,
+    safe: Address,
+    delegate: Address,
+    token: Address,
+    module: Option<Address>,
+    middleware: Arc<M>,
+) -> anyhow::Result<Allowance> {
+    let module = match module.or_else(|| default_module(chain_id)) {
+        Some(module) => module,
+        None => anyhow::bail!("no known allowance module on chain {chain_id}"),
+    };
+    let [amount, spent, reset_min, last_reset, nonce] = AllowanceModule::new(module, middleware)
+        .get_token_allowance(safe, delegate, token)
+        .call()
+        .await?;
+
+    Ok(Allowance {
+        amount,
+        spent,
+        reset_min: reset_min.as_u64(),
+        last_reset: last_reset.as_u64(),
+        nonce: nonce.as_u64(),
+    })
+}
+
+#[cfg(test)]
+#[test]
+fn test_transfer_calldata() {
+    let calldata = transfer_calldata(
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        Address::repeat_byte(3),
+        U256::exp10(18),
+        Address::repeat_byte(4),
+        vec![],
+    )
+    .unwrap();
+    // selector, 8 head words, then the length of the empty signature
+    assert_eq!(calldata.len(), 4 + 9 * 32);
+    assert_eq!(
+        U256::from_big_endian(&calldata[4 + 3 * 32..4 + 4 * 32]),
+        U256::exp10(18)
+    );
+
+    assert!(transfer_calldata(
+        Address::zero(),
+        Address::zero(),
+        Address::zero(),
+        U256::one() << 96,
+        Address::zero(),
+        vec![],
+    )
+    .is_err());
+}
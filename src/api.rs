@@ -1,18 +1,28 @@
-use crate::encoding::bytes_to_hex_string;
-use crate::safe::SignedSafePayload;
+use crate::admin::{FallbackHandlerKind, SafePolicy};
+use crate::clock::{Clock, TokioClock};
+use crate::constants::{DELETE_REQUEST_TYPE_HASH, SERVICE_DOMAIN_TYPE_HASH};
+use crate::encoding::{
+    address_eq_str, bytes_to_hex_string, checksum_for_chain, deserialize_decimal_u256,
+    hex_string_to_bytes, operation_to_u8,
+};
+use crate::error::{SafeError, ServiceValidationError};
+use crate::safe::{attempt_extract_nonce, is_executable, sort_and_join_sigs, SignedSafePayload};
 use crate::transaction::Transactionable;
-use crate::types::SafeTransaction;
+use crate::version::{SafeVersion, SingletonTable};
 use core::fmt::Debug;
+use ethers::abi::{self, Token};
 use ethers::types::transaction::eip712::Eip712;
-use ethers::types::Address;
-use ethers::utils::to_checksum;
+use ethers::types::{Address, Bytes, H256, U256};
+use ethers::utils::keccak256;
+use futures::{Stream, StreamExt};
 use lazy_static::lazy_static;
-use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use safe_client_gateway::common::models::data_decoded::Operation;
 use safe_client_gateway::common::models::page::Page;
 use safe_client_gateway::routes::transactions::models::details::{
-    DetailedExecutionInfo, TransactionData,
+    DetailedExecutionInfo, MultisigConfirmation, TransactionData,
 };
+use safe_client_gateway::routes::transactions::models::TransactionStatus;
 use safe_client_gateway::routes::{
     safes::models::SafeState,
     transactions::models::{
@@ -22,12 +32,37 @@ use safe_client_gateway::routes::{
 };
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
 
 const BASE_URL: &str = "https://safe-client.safe.global/v1/chains";
 
+/// Overrides [BASE_URL], e.g. to point the client at a mock service in tests
+pub const BASE_URL_ENV: &str = "SAFE_CLIENT_GATEWAY_URL";
+
+/// Upper bound on every request unless changed with [set_request_timeout], so a hung connection
+/// can't block forever
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+static REQUEST_TIMEOUT_MILLIS: AtomicU64 =
+    AtomicU64::new(DEFAULT_REQUEST_TIMEOUT.as_millis() as u64);
+
+/// Requests in flight at once when fetching many transactions
+const BULK_CONCURRENCY: usize = 8;
+
+/// Responses larger than this are rejected unless changed with [set_max_response_size]
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 16 * 1024 * 1024;
+
+static MAX_RESPONSE_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_RESPONSE_SIZE);
+
+/// Polling backs off up to this interval to respect the service's rate limits
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
 lazy_static! {
-    static ref CLIENT: reqwest::Client = reqwest::ClientBuilder::new()
+    pub(crate) static ref CLIENT: reqwest::Client = reqwest::ClientBuilder::new()
         .default_headers({
             reqwest::header::HeaderMap::from_iter(
                 [("cache-control", "no-cache")]
@@ -37,22 +72,125 @@ lazy_static! {
         })
         .build()
         .unwrap();
+
+    /// Sent with every request to the service, see [set_default_header]
+    static ref DEFAULT_HEADERS: RwLock<HeaderMap> = RwLock::new(HeaderMap::new());
 }
 
 pub(super) fn make_route(url: &str, route: &[&str]) -> String {
     format!("{}/{}", url, route.join("/"))
 }
 
+/// Adds a header to every request to the service, such as the API key the hosted service requires
+///
+/// Replaces an earlier value of the same header, values are marked sensitive to keep them out of logs
+pub fn set_default_header(name: &str, value: &str) -> anyhow::Result<()> {
+    let name = HeaderName::from_bytes(name.as_bytes())?;
+    let mut value = HeaderValue::from_str(value)?;
+    value.set_sensitive(true);
+    DEFAULT_HEADERS
+        .write()
+        .map_err(|_| anyhow::anyhow!("default headers lock poisoned"))?
+        .insert(name, value);
+    Ok(())
+}
+
+fn with_default_headers(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match DEFAULT_HEADERS.read() {
+        Ok(headers) => request.headers(headers.clone()),
+        Err(_) => request,
+    }
+}
+
+fn get(url: &str) -> reqwest::RequestBuilder {
+    with_default_headers(CLIENT.get(url))
+}
+
+fn post(url: &str) -> reqwest::RequestBuilder {
+    with_default_headers(CLIENT.post(url))
+}
+
+fn delete(url: &str) -> reqwest::RequestBuilder {
+    with_default_headers(CLIENT.delete(url))
+}
+
+/// Bounds every request to the service, including reading the response
+pub fn set_request_timeout(timeout: Duration) {
+    REQUEST_TIMEOUT_MILLIS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Sends a request with the configured timeout
+pub(crate) async fn send(request: reqwest::RequestBuilder) -> anyhow::Result<reqwest::Response> {
+    let timeout = Duration::from_millis(REQUEST_TIMEOUT_MILLIS.load(Ordering::Relaxed));
+    Ok(request.timeout(timeout).send().await?)
+}
+
+/// Caps the size of every response body read from the service, in bytes
+pub fn set_max_response_size(bytes: usize) {
+    MAX_RESPONSE_SIZE.store(bytes, Ordering::Relaxed);
+}
+
+/// Reads the body chunk by chunk, giving up as soon as it grows past the limit
+async fn bounded_text(mut response: reqwest::Response) -> anyhow::Result<String> {
+    let limit = MAX_RESPONSE_SIZE.load(Ordering::Relaxed);
+    let mut body = vec![];
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(SafeError::ResponseTooLarge(limit).into());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8(body)?)
+}
+
+/// A response other than 200, kept apart from other errors so polling can retry transient ones
+#[derive(Debug)]
+struct UnexpectedStatus {
+    status: reqwest::StatusCode,
+    message: String,
+}
+
+impl std::fmt::Display for UnexpectedStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for UnexpectedStatus {}
+
 async fn friendly_handle<T>(response: reqwest::Response) -> anyhow::Result<T>
 where
     T: Debug + DeserializeOwned,
 {
     let pretty_response = format!("{response:#?}");
-    if response.status() != reqwest::StatusCode::OK {
-        let text = response.text().await?;
-        anyhow::bail!("arbnormal status code\n{pretty_response}\nGot body\n{text:#?}",);
+    let status = response.status();
+    if status == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+        let text = bounded_text(response).await?;
+        if let Some(error) = ServiceValidationError::parse(&text) {
+            return Err(error.into());
+        }
+        return Err(UnexpectedStatus {
+            status,
+            message: format!("arbnormal status code\n{pretty_response}\nGot body\n{text:#?}"),
+        }
+        .into());
     }
-    let text = response.text().await?;
+    if status != reqwest::StatusCode::OK {
+        let text = bounded_text(response).await?;
+        return Err(UnexpectedStatus {
+            status,
+            message: format!("arbnormal status code\n{pretty_response}\nGot body\n{text:#?}"),
+        }
+        .into());
+    }
+    parse_body(response).await
+}
+
+async fn parse_body<T>(response: reqwest::Response) -> anyhow::Result<T>
+where
+    T: Debug + DeserializeOwned,
+{
+    let text = bounded_text(response).await?;
     match serde_json::from_str::<T>(&text) {
         Err(e) => {
             anyhow::bail!("failed to parse response: {}\n{text:#?}", e);
@@ -61,65 +199,775 @@ where
     }
 }
 
-async fn friendly_execute<T>(request: reqwest::RequestBuilder) -> anyhow::Result<T>
+/// [friendly_handle] that also returns the untouched body, to spot fields the types drop
+async fn friendly_handle_raw<T>(
+    response: reqwest::Response,
+) -> anyhow::Result<(T, serde_json::Value)>
 where
     T: Debug + DeserializeOwned,
 {
-    friendly_handle(request.send().await?).await
+    let raw: serde_json::Value = friendly_handle(response).await?;
+    match T::deserialize(&raw) {
+        Err(e) => {
+            anyhow::bail!("failed to parse response: {}\n{raw:#}", e);
+        }
+        Ok(x) => Ok((x, raw)),
+    }
+}
+
+pub(crate) async fn friendly_execute<T>(request: reqwest::RequestBuilder) -> anyhow::Result<T>
+where
+    T: Debug + DeserializeOwned,
+{
+    friendly_handle(send(request).await?).await
+}
+
+/// [friendly_execute] for services that answer with any 2xx, such as 201 for a created resource
+pub(crate) async fn friendly_execute_success<T>(
+    request: reqwest::RequestBuilder,
+) -> anyhow::Result<T>
+where
+    T: Debug + DeserializeOwned,
+{
+    let response = send(request).await?;
+    if response.status().is_success() {
+        parse_body(response).await
+    } else {
+        friendly_handle(response).await
+    }
+}
+
+fn base_url() -> String {
+    std::env::var(BASE_URL_ENV).unwrap_or_else(|_| BASE_URL.to_owned())
 }
 
 fn api_url(chain_id: u64, route: &[&str]) -> String {
     let chain_id_string = chain_id.to_string();
     let mut chain_id_and_route = vec![chain_id_string.as_ref()];
     chain_id_and_route.extend_from_slice(route);
-    make_route(BASE_URL, &chain_id_and_route)
+    make_route(&base_url(), &chain_id_and_route)
 }
 
-pub async fn safes(chain_id: u64, address: Address) -> anyhow::Result<SafeState> {
-    let checksummed_address = ethers::core::utils::to_checksum(&address, None);
+/// The deployment of the gateway, as reported by its `about` endpoint
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-deserialize", serde(deny_unknown_fields))]
+pub struct ServiceInfo {
+    pub name: String,
+    pub version: String,
+    pub build_number: Option<String>,
+}
+
+/// The parts of the gateway's chain config useful to check it serves the expected network
+///
+/// Deliberately partial, so left lenient with the `strict-deserialize` feature
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainInfo {
+    pub chain_id: String,
+    pub chain_name: String,
+    pub short_name: String,
+    pub l2: bool,
+    pub transaction_service: String,
+}
+
+/// A safe's token balances valued in one fiat currency
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-deserialize", serde(deny_unknown_fields))]
+pub struct Balances {
+    pub fiat_total: String,
+    pub items: Vec<Balance>,
+}
+
+/// One token the safe holds and what it is worth
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Balance {
+    pub token_info: BalanceToken,
+    /// In the token's smallest unit
+    pub balance: String,
+    pub fiat_balance: String,
+}
+
+/// The token of a [Balance], the native currency is listed at the zero address
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceToken {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: Option<u8>,
+}
+
+/// The first screen of a safe, see [overview]
+#[derive(Debug, Clone)]
+pub struct SafeOverview {
+    pub owners: Vec<Address>,
+    pub threshold: u32,
+    pub nonce: u64,
+    pub pending_count: usize,
+    /// The fiat total of [Balances]
+    pub total_balances: String,
+}
+
+/// The transaction service's estimate of the gas parameters of a safe transaction
+///
+/// Newer services only estimate `safeTxGas`, the other fields are then zero and None
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-deserialize", serde(deny_unknown_fields))]
+pub struct GasEstimate {
+    #[serde(deserialize_with = "deserialize_decimal_u256")]
+    pub safe_tx_gas: U256,
+    #[serde(default, deserialize_with = "deserialize_decimal_u256")]
+    pub base_gas: U256,
+    #[serde(default, deserialize_with = "deserialize_decimal_u256")]
+    pub gas_price: U256,
+    /// The nonce of the last executed transaction, None before the first one
+    #[serde(default)]
+    pub last_used_nonce: Option<u64>,
+}
+
+impl GasEstimate {
+    /// The nonce of the next transaction according to the chain, ignoring queued ones
+    pub fn next_nonce(&self) -> u64 {
+        self.last_used_nonce.map_or(0, |nonce| nonce + 1)
+    }
+}
+
+/// Timestamps from the service, parsed from ISO-8601 with the `chrono` feature
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::Utc>;
+#[cfg(not(feature = "chrono"))]
+pub type Timestamp = String;
+
+/// The gateway reports times such as `submitted_at` and `executed_at` as unix milliseconds
+#[cfg(feature = "chrono")]
+pub fn timestamp_from_millis(millis: i64) -> Option<Timestamp> {
+    use chrono::TimeZone;
+    chrono::Utc.timestamp_millis_opt(millis).single()
+}
+
+/// How and by whom a safe was deployed
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-deserialize", serde(deny_unknown_fields))]
+pub struct CreationInfo {
+    pub created: Timestamp,
+    pub creator: Address,
+    pub transaction_hash: H256,
+    /// None for safes not deployed through a proxy factory
+    pub factory_address: Option<Address>,
+    pub master_copy: Option<Address>,
+    pub setup_data: Option<Bytes>,
+    /// The service's decoding of `setup_data`
+    #[serde(default)]
+    pub data_decoded: Option<serde_json::Value>,
+}
+
+/// Useful as a health check before relying on the service
+pub async fn service_info() -> anyhow::Result<ServiceInfo> {
+    let base_url = base_url();
+    let root = base_url.trim_end_matches("/v1/chains");
+    friendly_execute(get(&make_route(root, &["about"]))).await
+}
+
+pub async fn chain_info(chain_id: u64) -> anyhow::Result<ChainInfo> {
+    let info: ChainInfo = friendly_execute(get(&api_url(chain_id, &[]))).await?;
+    if info.chain_id != chain_id.to_string() {
+        anyhow::bail!(
+            "asked for chain {chain_id} but the service answered for chain {}",
+            info.chain_id
+        );
+    }
+    Ok(info)
+}
+
+async fn safes_response(chain_id: u64, address: Address) -> anyhow::Result<reqwest::Response> {
+    let checksummed_address = checksum_for_chain(&address, chain_id);
     debug!("getting safe {}", checksummed_address);
-    friendly_execute(CLIENT.get(&api_url(chain_id, &["safes", &checksummed_address]))).await
+    let response = send(get(&api_url(chain_id, &["safes", &checksummed_address]))).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(SafeError::SafeNotFound(address).into());
+    }
+    Ok(response)
+}
+
+pub async fn safes(chain_id: u64, address: Address) -> anyhow::Result<SafeState> {
+    friendly_handle(safes_response(chain_id, address).await?).await
 }
 
+/// [safes] along with the body as returned by the service
+pub async fn safes_raw(
+    chain_id: u64,
+    address: Address,
+) -> anyhow::Result<(SafeState, serde_json::Value)> {
+    friendly_handle_raw(safes_response(chain_id, address).await?).await
+}
+
+/// The owners and threshold of a safe, as reported by the service
+pub async fn policy(chain_id: u64, address: Address) -> anyhow::Result<SafePolicy> {
+    let safe_config = safes(chain_id, address).await?.safe_config;
+    Ok(SafePolicy {
+        owners: safe_config
+            .owners
+            .iter()
+            .map(|owner| owner.value.parse())
+            .collect::<Result<_, _>>()?,
+        threshold: u32::try_from(safe_config.threshold)?,
+    })
+}
+
+/// The enabled modules of a safe, as reported by the service
+pub async fn modules(chain_id: u64, address: Address) -> anyhow::Result<Vec<Address>> {
+    Ok(safes(chain_id, address)
+        .await?
+        .safe_config
+        .modules
+        .unwrap_or_default()
+        .iter()
+        .map(|module| module.value.parse())
+        .collect::<Result<_, _>>()?)
+}
+
+/// Token balances of a safe, valued in `fiat` (e.g. "USD")
+pub async fn balances(chain_id: u64, address: Address, fiat: &str) -> anyhow::Result<Balances> {
+    let checksummed_address = checksum_for_chain(&address, chain_id);
+    debug!("getting {} balances of safe {}", fiat, checksummed_address);
+    friendly_execute(get(&api_url(
+        chain_id,
+        &["safes", &checksummed_address, "balances", fiat],
+    )))
+    .await
+}
+
+/// Fetches the safe, its queue and its balances concurrently, failing with the first error
+pub async fn overview(chain_id: u64, address: Address, fiat: &str) -> anyhow::Result<SafeOverview> {
+    let (state, pending, balances) = futures::try_join!(
+        safes(chain_id, address),
+        queued(chain_id, address),
+        balances(chain_id, address, fiat),
+    )?;
+    let safe_config = state.safe_config;
+    Ok(SafeOverview {
+        owners: safe_config
+            .owners
+            .iter()
+            .map(|owner| owner.value.parse())
+            .collect::<Result<_, _>>()?,
+        threshold: u32::try_from(safe_config.threshold)?,
+        nonce: safe_config.nonce,
+        pending_count: pending.len(),
+        total_balances: balances.fiat_total,
+    })
+}
+
+/// The deployment of a safe, from the transaction service the gateway is backed by
+pub async fn creation_info(chain_id: u64, address: Address) -> anyhow::Result<CreationInfo> {
+    let transaction_service = chain_info(chain_id).await?.transaction_service;
+    let checksummed_address = checksum_for_chain(&address, chain_id);
+    debug!("getting creation of safe {}", checksummed_address);
+    friendly_execute(get(&make_route(
+        transaction_service.trim_end_matches('/'),
+        &["api", "v1", "safes", &checksummed_address, "creation", ""],
+    )))
+    .await
+}
+
+/// Which fallback handler the safe uses, e.g. to know whether EIP-1271 signatures are checked
+pub async fn fallback_handler_kind(
+    chain_id: u64,
+    address: Address,
+) -> anyhow::Result<FallbackHandlerKind> {
+    let handler = safes(chain_id, address)
+        .await?
+        .safe_config
+        .fallback_handler
+        .map(|handler| handler.value.parse::<Address>())
+        .transpose()?;
+    Ok(FallbackHandlerKind::of(handler))
+}
+
+/// The guard checking the safe's transactions, if one is set
+pub async fn guard(chain_id: u64, address: Address) -> anyhow::Result<Option<Address>> {
+    Ok(safes(chain_id, address)
+        .await?
+        .safe_config
+        .guard
+        .map(|guard| guard.value.parse::<Address>())
+        .transpose()?
+        .filter(|guard| !guard.is_zero()))
+}
+
+/// The reported version of a safe, or the version of its singleton in `singletons` when it is null
+pub fn resolved_version(state: &SafeState, singletons: &SingletonTable) -> Option<SafeVersion> {
+    let config = &state.safe_config;
+    if let Some(version) = config.version.as_deref().and_then(|v| v.parse().ok()) {
+        return Some(version);
+    }
+    singletons.lookup(
+        config.chain_id.parse().ok()?,
+        config.implementation.value.parse().ok()?,
+    )
+}
+
+/// The version dependent gotchas of a safe, see [compatibility_report]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatibilityReport {
+    pub version: Option<SafeVersion>,
+    /// Assumed true when the version can't be resolved
+    pub domain_includes_chain_id: bool,
+    pub supports_eip1271: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Summarizes what a safe supports from its state, without further requests
+pub fn compatibility_report_for(
+    state: &SafeState,
+    singletons: &SingletonTable,
+) -> anyhow::Result<CompatibilityReport> {
+    let version = resolved_version(state, singletons);
+    let handler = state
+        .safe_config
+        .fallback_handler
+        .as_ref()
+        .map(|handler| handler.value.parse::<Address>())
+        .transpose()?;
+    let handler_kind = FallbackHandlerKind::of(handler);
+
+    let mut warnings = vec![];
+    match version {
+        None => warnings.push(format!(
+            "unknown singleton {}: assuming the current EIP-712 domain",
+            state.safe_config.implementation.value
+        )),
+        Some(version) if !version.domain_includes_chain_id() => warnings.push(format!(
+            "v{version}: domain separator omits chainId, ensure signing matches"
+        )),
+        Some(_) => {}
+    }
+    match (handler_kind, handler) {
+        (FallbackHandlerKind::None, _) => warnings
+            .push("no fallback handler: EIP-1271 signatures and safe messages fail".to_owned()),
+        (FallbackHandlerKind::Unknown, Some(handler)) => warnings.push(format!(
+            "unknown fallback handler {handler:?}: EIP-1271 support can't be told off-chain"
+        )),
+        _ => {}
+    }
+
+    Ok(CompatibilityReport {
+        version,
+        domain_includes_chain_id: version.map_or(true, |v| v.domain_includes_chain_id()),
+        supports_eip1271: handler_kind.supports_eip1271(),
+        warnings,
+    })
+}
+
+/// Asks the transaction service the gateway is backed by to estimate the gas of `tx`, made with
+/// its own operation
+pub async fn estimate_gas<T: Transactionable>(
+    chain_id: u64,
+    address: Address,
+    tx: &T,
+) -> anyhow::Result<GasEstimate> {
+    let transaction_service = chain_info(chain_id).await?.transaction_service;
+    let checksummed_address = checksum_for_chain(&address, chain_id);
+    debug!("estimating gas for safe {}", checksummed_address);
+    friendly_execute(
+        post(&make_route(
+            transaction_service.trim_end_matches('/'),
+            &[
+                "api",
+                "v1",
+                "safes",
+                &checksummed_address,
+                "multisig-transactions",
+                "estimations",
+                "",
+            ],
+        ))
+        .json(&serde_json::json!({
+            "to": checksum_for_chain(&tx.to(), chain_id),
+            "value": tx.value().to_string(),
+            "data": format!("0x{}", bytes_to_hex_string(tx.calldata()?)),
+            "operation": operation_to_u8(tx.operation()),
+        })),
+    )
+    .await
+}
+
+/// Fetches the safe and reports what its version and fallback handler support
+pub async fn compatibility_report(
+    chain_id: u64,
+    address: Address,
+) -> anyhow::Result<CompatibilityReport> {
+    compatibility_report_for(&safes(chain_id, address).await?, &SingletonTable::default())
+}
+
+#[derive(Deserialize, Debug)]
+#[cfg_attr(feature = "strict-deserialize", serde(deny_unknown_fields))]
+struct OwnedSafes {
+    safes: Vec<String>,
+}
+
+/// The safes `owner` is an owner of
+pub async fn safes_for_owner(chain_id: u64, owner: Address) -> anyhow::Result<Vec<Address>> {
+    let checksummed_address = checksum_for_chain(&owner, chain_id);
+    debug!("getting safes owned by {}", checksummed_address);
+    let owned: OwnedSafes = friendly_execute(get(&api_url(
+        chain_id,
+        &["owners", &checksummed_address, "safes"],
+    )))
+    .await?;
+    Ok(owned
+        .safes
+        .iter()
+        .map(|safe| safe.parse())
+        .collect::<Result<_, _>>()?)
+}
+
+/// Follows the `next` cursor so busy safes aren't truncated to the first page
 pub async fn queued(chain_id: u64, address: Address) -> anyhow::Result<Vec<TransactionSummary>> {
-    let checksummed_address = ethers::core::utils::to_checksum(&address, None);
+    let checksummed_address = checksum_for_chain(&address, chain_id);
     debug!("getting queue for safe {}", checksummed_address);
-    let tx_summaries: Vec<TransactionSummary> =
-        friendly_execute::<Page<TransactionListItem>>(CLIENT.get(&api_url(
-            chain_id,
-            &["safes", &checksummed_address, "transactions", "queued"],
-        )))
-        .await?
-        .results
-        .into_iter()
-        .flat_map(|tli| match tli {
+    let mut url = api_url(
+        chain_id,
+        &["safes", &checksummed_address, "transactions", "queued"],
+    );
+    let mut tx_summaries: Vec<TransactionSummary> = vec![];
+    loop {
+        let page = friendly_execute::<Page<TransactionListItem>>(get(&url)).await?;
+        tx_summaries.extend(page.results.into_iter().flat_map(|tli| match tli {
             TransactionListItem::Transaction { transaction, .. } => Some(transaction),
             _ => None,
-        })
-        .collect();
+        }));
+        match page.next {
+            Some(next) => url = next,
+            None => break,
+        }
+    }
     debug!("received {} queued transactions", tx_summaries.len());
     Ok(tx_summaries)
 }
 
+/// The queue of every safe `owner` owns, fetched concurrently
+///
+/// Each safe's result is independent so one failed request doesn't fail the others
+pub async fn all_pending_for_owner(
+    chain_id: u64,
+    owner: Address,
+) -> anyhow::Result<Vec<(Address, anyhow::Result<Vec<TransactionSummary>>)>> {
+    let owned = safes_for_owner(chain_id, owner).await?;
+    Ok(futures::stream::iter(owned)
+        .map(|safe| async move { (safe, queued(chain_id, safe).await) })
+        .buffered(BULK_CONCURRENCY)
+        .collect()
+        .await)
+}
+
+/// Polls the queue every `interval`, yielding each transaction the first time it is seen
+///
+/// The first poll yields everything already queued. Transactions that leave the queue (executed or
+/// replaced) are forgotten so the seen set stays bounded, failed polls are yielded as errors and
+/// polling continues
+pub fn watch_pending(
+    chain_id: u64,
+    address: Address,
+    interval: Duration,
+) -> impl Stream<Item = anyhow::Result<TransactionSummary>> {
+    watch_pending_with_clock(chain_id, address, interval, TokioClock)
+}
+
+/// `watch_pending` sleeping on `clock` between polls
+pub fn watch_pending_with_clock<C: Clock + 'static>(
+    chain_id: u64,
+    address: Address,
+    interval: Duration,
+    clock: C,
+) -> impl Stream<Item = anyhow::Result<TransactionSummary>> {
+    struct WatchState<C> {
+        seen: HashSet<String>,
+        ready: VecDeque<TransactionSummary>,
+        polled: bool,
+        clock: C,
+    }
+
+    let state = WatchState {
+        seen: HashSet::new(),
+        ready: VecDeque::new(),
+        polled: false,
+        clock,
+    };
+    futures::stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(tx) = state.ready.pop_front() {
+                return Some((Ok(tx), state));
+            }
+            if state.polled {
+                state.clock.sleep(interval).await;
+            }
+            state.polled = true;
+
+            let queue = match queued(chain_id, address).await {
+                Ok(queue) => queue,
+                Err(e) => return Some((Err(e), state)),
+            };
+            let queued_ids: HashSet<&str> = queue.iter().map(|tx| tx.id.as_str()).collect();
+            state.seen.retain(|id| queued_ids.contains(id.as_str()));
+            for tx in queue {
+                if state.seen.insert(tx.id.clone()) {
+                    state.ready.push_back(tx);
+                }
+            }
+        }
+    })
+}
+
+/// One page of transfers into the safe, pass the previous page's `next` to continue from it
+pub async fn incoming_transfers(
+    chain_id: u64,
+    address: Address,
+    next: Option<&str>,
+) -> anyhow::Result<Page<TransactionListItem>> {
+    let checksummed_address = checksum_for_chain(&address, chain_id);
+    debug!("getting incoming transfers of {}", checksummed_address);
+    let url = match next {
+        Some(next) => next.to_owned(),
+        None => api_url(
+            chain_id,
+            &["safes", &checksummed_address, "incoming-transfers", ""],
+        ),
+    };
+    friendly_execute(get(&url)).await
+}
+
 async fn transaction_details(
     chain_id: u64,
     details_id: &str,
 ) -> anyhow::Result<TransactionDetails> {
     debug!("getting details for transaction {}", &details_id);
-    friendly_execute(CLIENT.get(&api_url(chain_id, &["transactions", details_id]))).await
+    friendly_execute(get(&api_url(chain_id, &["transactions", details_id]))).await
+}
+
+/// The details of a transaction along with the body as returned by the service
+pub async fn transaction_details_raw(
+    chain_id: u64,
+    details_id: &str,
+) -> anyhow::Result<(TransactionDetails, serde_json::Value)> {
+    debug!("getting details for transaction {}", &details_id);
+    friendly_handle_raw(send(get(&api_url(chain_id, &["transactions", details_id]))).await?).await
+}
+
+/// Worth polling again: the service was unreachable, too slow, failing or rate limiting
+fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return e.is_connect() || e.is_timeout();
+        }
+        cause.downcast_ref::<UnexpectedStatus>().map_or(false, |e| {
+            e.status.is_server_error() || e.status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        })
+    })
+}
+
+/// Polls a transaction until `done` returns true, doubling the interval after every attempt
+///
+/// Transient failures are retried until the deadline, the timeout then carries the last of them
+async fn poll_transaction<F>(
+    chain_id: u64,
+    safe_tx_hash: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+    clock: &dyn Clock,
+    done: F,
+) -> anyhow::Result<TransactionDetails>
+where
+    F: Fn(&TransactionDetails) -> anyhow::Result<bool>,
+{
+    let deadline = clock.now() + timeout;
+    let mut interval = poll_interval;
+    let mut last_error = None;
+    loop {
+        match transaction_details(chain_id, safe_tx_hash).await {
+            Ok(details) if done(&details)? => return Ok(details),
+            Ok(_) => last_error = None,
+            Err(e) if is_transient(&e) => {
+                warn!("polling {safe_tx_hash} failed: {e:#}");
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+
+        if clock.now() + interval > deadline {
+            return Err(match last_error {
+                Some(e) => e.context(SafeError::Timeout(timeout)),
+                None => SafeError::Timeout(timeout).into(),
+            });
+        }
+        clock.sleep(interval).await;
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
 }
 
+/// Waits until the transaction has collected enough confirmations to be executed
+pub async fn wait_for_confirmations(
+    chain_id: u64,
+    safe_tx_hash: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> anyhow::Result<TransactionDetails> {
+    wait_for_confirmations_with_clock(chain_id, safe_tx_hash, timeout, poll_interval, &TokioClock)
+        .await
+}
+
+/// `wait_for_confirmations` measuring the timeout and sleeping on `clock`
+pub async fn wait_for_confirmations_with_clock(
+    chain_id: u64,
+    safe_tx_hash: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+    clock: &dyn Clock,
+) -> anyhow::Result<TransactionDetails> {
+    poll_transaction(
+        chain_id,
+        safe_tx_hash,
+        timeout,
+        poll_interval,
+        clock,
+        |details| Ok(is_executable(details)),
+    )
+    .await
+}
+
+/// The confirmations of each transaction, in order, fetched concurrently
+///
+/// Each result is independent so one failed request doesn't fail the others
+pub async fn confirmations_bulk(
+    chain_id: u64,
+    safe_tx_hashes: &[&str],
+) -> Vec<anyhow::Result<Vec<MultisigConfirmation>>> {
+    futures::stream::iter(safe_tx_hashes)
+        .map(|safe_tx_hash| async move {
+            match transaction_details(chain_id, safe_tx_hash)
+                .await?
+                .detailed_execution_info
+            {
+                Some(DetailedExecutionInfo::Multisig(multisig)) => Ok(multisig.confirmations),
+                _ => anyhow::bail!("{safe_tx_hash} is not a multisig transaction"),
+            }
+        })
+        .buffered(BULK_CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// Waits until the transaction has been executed on chain and returns the chain transaction hash
+///
+/// Errors if the execution failed or the transaction was cancelled by another one at its nonce
+pub async fn wait_for_execution(
+    chain_id: u64,
+    safe_tx_hash: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> anyhow::Result<H256> {
+    wait_for_execution_with_clock(chain_id, safe_tx_hash, timeout, poll_interval, &TokioClock).await
+}
+
+/// `wait_for_execution` measuring the timeout and sleeping on `clock`
+pub async fn wait_for_execution_with_clock(
+    chain_id: u64,
+    safe_tx_hash: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+    clock: &dyn Clock,
+) -> anyhow::Result<H256> {
+    let executed = |details: &TransactionDetails| match details.tx_status {
+        TransactionStatus::Success => Ok(true),
+        TransactionStatus::Failed => match &details.tx_hash {
+            Some(tx_hash) => Err(SafeError::ExecutionFailed(tx_hash.parse()?).into()),
+            None => anyhow::bail!("transaction {safe_tx_hash} failed"),
+        },
+        TransactionStatus::Cancelled => Err(SafeError::ExecutionCancelled.into()),
+        _ => Ok(false),
+    };
+    let details = poll_transaction(
+        chain_id,
+        safe_tx_hash,
+        timeout,
+        poll_interval,
+        clock,
+        executed,
+    )
+    .await?;
+
+    match details.tx_hash {
+        Some(tx_hash) => Ok(tx_hash.parse()?),
+        None => anyhow::bail!("executed transaction {safe_tx_hash} has no transaction hash"),
+    }
+}
+
+/// How [queued_details] sorts the queue, the service's own order is not guaranteed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueOrdering {
+    /// Lowest nonce first, the order transactions can be executed in
+    #[default]
+    Nonce,
+    NonceDescending,
+    /// Oldest proposal first
+    Submitted,
+    SubmittedDescending,
+}
+
+/// The details of every queued transaction, lowest nonce first
 pub async fn queued_details(
     chain_id: u64,
     address: Address,
+) -> anyhow::Result<Vec<TransactionDetails>> {
+    queued_details_ordered(chain_id, address, QueueOrdering::default()).await
+}
+
+pub async fn queued_details_ordered(
+    chain_id: u64,
+    address: Address,
+    ordering: QueueOrdering,
 ) -> anyhow::Result<Vec<TransactionDetails>> {
     let all_queued = queued(chain_id, address).await?;
-    futures::future::try_join_all(
+    let mut details = futures::future::try_join_all(
         all_queued
             .iter()
             .map(|tx| transaction_details(chain_id, &tx.id)),
     )
-    .await
+    .await?;
+
+    let submitted_at = |details: &TransactionDetails| match &details.detailed_execution_info {
+        Some(DetailedExecutionInfo::Multisig(multisig)) => Some(multisig.submitted_at),
+        _ => None,
+    };
+    // stable sorts keep the service's order among equal keys
+    match ordering {
+        QueueOrdering::Nonce => details.sort_by_key(attempt_extract_nonce),
+        QueueOrdering::NonceDescending => {
+            details.sort_by_key(|details| std::cmp::Reverse(attempt_extract_nonce(details)))
+        }
+        QueueOrdering::Submitted => details.sort_by_key(submitted_at),
+        QueueOrdering::SubmittedDescending => {
+            details.sort_by_key(|details| std::cmp::Reverse(submitted_at(details)))
+        }
+    }
+    Ok(details)
+}
+
+/// All queued transactions competing for the same nonce, only one of them can be executed
+pub async fn conflicts(
+    chain_id: u64,
+    address: Address,
+    nonce: u64,
+) -> anyhow::Result<Vec<TransactionDetails>> {
+    Ok(queued_details(chain_id, address)
+        .await?
+        .into_iter()
+        .filter(|details| attempt_extract_nonce(details) == Some(nonce))
+        .collect())
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -135,7 +983,11 @@ pub struct MultisigTransactionRequest {
     pub base_gas: String,
     pub gas_price: String,
     pub gas_token: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::encoding::deserialize_zero_address_as_none"
+    )]
     pub refund_receiver: Option<String>,
     pub safe_tx_hash: String,
     pub sender: String,
@@ -152,26 +1004,37 @@ impl<T: Transactionable> From<SignedSafePayload<T>> for MultisigTransactionReque
             payload,
             signature,
             sender,
+            origin,
         }: SignedSafePayload<T>,
     ) -> Self {
         let hash = payload.encode_eip712().unwrap();
+        let chain_id = payload.chain_id;
         let inner = payload.tx;
+        let calldata = inner.calldata().unwrap();
+        if calldata.is_empty()
+            && inner.value().is_zero()
+            && matches!(payload.operation, Operation::CALL)
+        {
+            warn!(
+                "proposing a CALL to {} with no value and no calldata, this is a no-op",
+                checksum_for_chain(&inner.to(), chain_id)
+            );
+        }
         Self {
-            to: to_checksum(&inner.to(), None),
-            // todo check encoding
+            to: checksum_for_chain(&inner.to(), chain_id),
             value: inner.value().to_string(),
-            data: Option::Some("0x".to_owned() + &bytes_to_hex_string(inner.calldata().unwrap())),
+            data: Option::Some("0x".to_owned() + &bytes_to_hex_string(calldata)),
             operation: payload.operation,
             safe_tx_gas: payload.safe_tx_gas.to_string(),
             base_gas: payload.base_gas.to_string(),
             gas_price: payload.gas_price.to_string(),
-            gas_token: to_checksum(&payload.gas_token, None),
-            refund_receiver: Some(to_checksum(&payload.refund_receiver, None)),
+            gas_token: checksum_for_chain(&payload.gas_token, chain_id),
+            refund_receiver: Some(checksum_for_chain(&payload.refund_receiver, chain_id)),
             nonce: payload.nonce.to_string(),
             signature: Option::Some("0x".to_owned() + &signature.to_string()),
             safe_tx_hash: "0x".to_owned() + &bytes_to_hex_string(hash),
-            sender: to_checksum(&sender, None),
-            origin: Option::None,
+            sender: checksum_for_chain(&sender, chain_id),
+            origin,
         }
     }
 }
@@ -183,20 +1046,215 @@ pub async fn propose<T: Transactionable>(
     let address = tx.payload.safe_address;
     let tx = MultisigTransactionRequest::from(tx);
     friendly_execute(
-        CLIENT
-            .post(&api_url(
-                chain_id,
-                &[
-                    "transactions",
-                    &ethers::core::utils::to_checksum(&address, None),
-                    "propose",
-                ],
-            ))
-            .json(&tx),
+        post(&api_url(
+            chain_id,
+            &[
+                "transactions",
+                &checksum_for_chain(&address, chain_id),
+                "propose",
+            ],
+        ))
+        .json(&tx),
     )
     .await
 }
 
+/// Proposes then waits up to `timeout` for the service to index the proposal, as reading it back
+/// right after proposing can 404
+pub async fn propose_and_confirm_indexed<T: Transactionable>(
+    tx: SignedSafePayload<T>,
+    timeout: Duration,
+) -> anyhow::Result<TransactionDetails> {
+    propose_and_confirm_indexed_with_clock(tx, timeout, &TokioClock).await
+}
+
+/// `propose_and_confirm_indexed` measuring the timeout and sleeping on `clock`
+pub async fn propose_and_confirm_indexed_with_clock<T: Transactionable>(
+    tx: SignedSafePayload<T>,
+    timeout: Duration,
+    clock: &dyn Clock,
+) -> anyhow::Result<TransactionDetails> {
+    let chain_id = tx.payload.chain_id;
+    let safe_tx_hash = "0x".to_owned() + &bytes_to_hex_string(tx.payload.encode_eip712()?);
+    propose(tx).await?;
+
+    let deadline = clock.now() + timeout;
+    let mut interval = Duration::from_millis(250);
+    loop {
+        match transaction_details(chain_id, &safe_tx_hash).await {
+            Ok(details) => return Ok(details),
+            Err(e) if clock.now() + interval > deadline => {
+                return Err(e.context(format!("{safe_tx_hash} was not indexed after {timeout:?}")))
+            }
+            Err(e) => debug!("{safe_tx_hash} not indexed yet: {e}"),
+        }
+        clock.sleep(interval).await;
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// Proposes independent transactions concurrently, returning each safe tx hash in order
+///
+/// Each needs its own nonce, a transaction reusing an earlier one's nonce fails without being sent.
+/// One failed proposal doesn't stop the others
+pub async fn propose_batch<T: Transactionable>(
+    signed: Vec<SignedSafePayload<T>>,
+) -> Vec<anyhow::Result<H256>> {
+    let mut nonces = HashSet::new();
+    let duplicates: Vec<bool> = signed
+        .iter()
+        .map(|tx| !nonces.insert((tx.payload.safe_address, tx.payload.nonce)))
+        .collect();
+
+    futures::stream::iter(signed.into_iter().zip(duplicates))
+        .map(|(tx, duplicate)| async move {
+            if duplicate {
+                anyhow::bail!(
+                    "nonce {} is already used earlier in the batch",
+                    tx.payload.nonce
+                );
+            }
+            let safe_tx_hash = H256(tx.payload.encode_eip712()?);
+            propose(tx).await?;
+            Ok(safe_tx_hash)
+        })
+        .buffered(BULK_CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// Reads a proposal back and checks the service stored exactly what was signed, so a buggy or
+/// malicious service can't show owners data the signature doesn't cover
+pub async fn verify_proposal<T: Transactionable>(
+    signed: &SignedSafePayload<T>,
+) -> anyhow::Result<()> {
+    let payload = &signed.payload;
+    let safe_tx_hash = H256(payload.encode_eip712()?);
+    let details = transaction_details(payload.chain_id, &format!("{safe_tx_hash:?}")).await?;
+
+    let (Some(tx_data), Some(DetailedExecutionInfo::Multisig(info))) =
+        (&details.tx_data, &details.detailed_execution_info)
+    else {
+        anyhow::bail!("{safe_tx_hash:?} is not a multisig transaction with data");
+    };
+    let decimal_eq = |stored: &str, signed: U256| U256::from_dec_str(stored).ok() == Some(signed);
+    let stored_data = match tx_data.hex_data.as_deref() {
+        Some(data) => hex_string_to_bytes(data)?,
+        None => vec![],
+    };
+
+    let mut mismatches = vec![];
+    for (field, matches) in [
+        (
+            "safeTxHash",
+            info.safe_tx_hash.parse::<H256>().ok() == Some(safe_tx_hash),
+        ),
+        ("to", address_eq_str(&payload.tx.to(), &tx_data.to.value)),
+        (
+            "value",
+            decimal_eq(tx_data.value.as_deref().unwrap_or("0"), payload.tx.value()),
+        ),
+        ("data", stored_data == payload.tx.calldata()?),
+        (
+            "operation",
+            operation_to_u8(tx_data.operation) == operation_to_u8(payload.operation),
+        ),
+        (
+            "safeTxGas",
+            decimal_eq(&info.safe_tx_gas, payload.safe_tx_gas),
+        ),
+        ("baseGas", decimal_eq(&info.base_gas, payload.base_gas)),
+        ("gasPrice", decimal_eq(&info.gas_price, payload.gas_price)),
+        (
+            "gasToken",
+            address_eq_str(&payload.gas_token, &info.gas_token),
+        ),
+        (
+            "refundReceiver",
+            address_eq_str(&payload.refund_receiver, &info.refund_receiver.value),
+        ),
+        ("nonce", U256::from(info.nonce) == payload.nonce),
+    ] {
+        if !matches {
+            mismatches.push(field);
+        }
+    }
+    if !mismatches.is_empty() {
+        anyhow::bail!(
+            "the service stored {safe_tx_hash:?} with a different {}",
+            mismatches.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Builds and logs the proposal body at INFO level without sending it
+pub fn propose_dry_run<T: Transactionable>(tx: SignedSafePayload<T>) -> anyhow::Result<()> {
+    let chain_id = tx.payload.chain_id;
+    let address = tx.payload.safe_address;
+    let tx = MultisigTransactionRequest::from(tx);
+    info!(
+        "dry run: would propose to safe {} on chain {}\n{}",
+        checksum_for_chain(&address, chain_id),
+        chain_id,
+        serde_json::to_string_pretty(&tx)?
+    );
+    Ok(())
+}
+
+/// The current time step of the service's deletion signatures, it also accepts the previous one
+pub fn current_totp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 3600
+}
+
+/// The EIP-712 hash the proposer signs to delete an unexecuted transaction
+///
+/// DeleteRequest(bytes32 safeTxHash,uint256 totp) in the domain
+/// ("Safe Transaction Service", "1.0", chainId, safe)
+pub fn deletion_hash(chain_id: u64, safe_address: Address, safe_tx_hash: H256, totp: u64) -> H256 {
+    let domain_separator = keccak256(abi::encode(&[
+        Token::FixedBytes(SERVICE_DOMAIN_TYPE_HASH.clone()),
+        Token::FixedBytes(keccak256("Safe Transaction Service").to_vec()),
+        Token::FixedBytes(keccak256("1.0").to_vec()),
+        Token::Uint(chain_id.into()),
+        Token::Address(safe_address),
+    ]));
+    let struct_hash = keccak256(abi::encode(&[
+        Token::FixedBytes(DELETE_REQUEST_TYPE_HASH.clone()),
+        Token::FixedBytes(safe_tx_hash.as_bytes().to_vec()),
+        Token::Uint(totp.into()),
+    ]));
+
+    H256(keccak256(
+        [&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat(),
+    ))
+}
+
+/// Deletes an unexecuted transaction, `signature` is the proposer's signature of [deletion_hash]
+pub async fn delete_transaction(
+    chain_id: u64,
+    safe_tx_hash: H256,
+    signature: String,
+) -> anyhow::Result<()> {
+    let safe_tx_hash = "0x".to_owned() + &bytes_to_hex_string(safe_tx_hash);
+    debug!("deleting transaction {}", safe_tx_hash);
+    let response = send(
+        delete(&api_url(chain_id, &["transactions", &safe_tx_hash]))
+            .json(&serde_json::json!({ "signature": signature })),
+    )
+    .await?;
+    if !response.status().is_success() {
+        let pretty_response = format!("{response:#?}");
+        let text = bounded_text(response).await?;
+        anyhow::bail!("failed to delete transaction\n{pretty_response}\nGot body\n{text:#?}");
+    }
+    Ok(())
+}
+
 /// returns the first pending transactions that matches this calldata
 pub async fn match_calldata<T: Transactionable>(
     tx: &T,
@@ -233,7 +1291,7 @@ pub fn extract_sigs_from_details<T: Transactionable>(details: &TransactionDetail
         }
     };
 
-    SafeTransaction::<T>::sort_and_join_sigs(
+    sort_and_join_sigs(
         &confirms
             .into_iter()
             .filter_map(|c| match c.signer.value.parse::<ethers::types::Address>() {
@@ -253,19 +1311,177 @@ pub fn extract_sigs_from_details<T: Transactionable>(details: &TransactionDetail
 pub fn is_signed(details: &TransactionDetails, signer: Address) -> bool {
     match details.detailed_execution_info.clone() {
         Some(info) => match info {
-            DetailedExecutionInfo::Multisig(multisig_info) => {
-                let all_signers = multisig_info
-                    .confirmations
-                    .into_iter()
-                    .map(|confirm| confirm.signer.value.parse().unwrap())
-                    .collect::<Vec<Address>>();
-
-                println!("all signers: {:?}", all_signers);
-
-                all_signers.contains(&signer)
-            }
+            DetailedExecutionInfo::Multisig(multisig_info) => multisig_info
+                .confirmations
+                .iter()
+                .any(|confirm| address_eq_str(&signer, &confirm.signer.value)),
             _ => false,
         },
         None => false,
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_zero_refund_receiver() {
+    let request = |refund_receiver: serde_json::Value| {
+        serde_json::from_value::<MultisigTransactionRequest>(serde_json::json!({
+            "to": "0x0000000000000000000000000000000000000001",
+            "value": "0",
+            "data": "0x",
+            "nonce": "0",
+            "operation": 0,
+            "safeTxGas": "0",
+            "baseGas": "0",
+            "gasPrice": "0",
+            "gasToken": "0x0000000000000000000000000000000000000000",
+            "refundReceiver": refund_receiver,
+            "safeTxHash": "0x0f7b372b07f04519dfa3c6e54766a16719474099fe10705fd5cd5567403134cd",
+            "sender": "0x0000000000000000000000000000000000000001",
+        }))
+        .unwrap()
+    };
+
+    assert_eq!(request(serde_json::Value::Null).refund_receiver, None);
+    assert_eq!(
+        request("0x0000000000000000000000000000000000000000".into()).refund_receiver,
+        None
+    );
+    assert_eq!(
+        request("0x0000000000000000000000000000000000000002".into()).refund_receiver,
+        Some("0x0000000000000000000000000000000000000002".to_owned())
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_proposal_body() {
+    use crate::safe::SafeTransaction;
+    use crate::transaction::RawCall;
+    use ethers::types::Signature;
+
+    let one: Address = "0x0000000000000000000000000000000000000001"
+        .parse()
+        .unwrap();
+    let payload = SafeTransaction {
+        tx: RawCall {
+            to: one,
+            value: U256::zero(),
+            data: H256::zero().as_bytes().to_vec(),
+        },
+        safe_address: "0x783c330A7A4968A08ce100A16ac27Ff2cCfAEbdf"
+            .parse()
+            .unwrap(),
+        chain_id: 1,
+        safe_tx_gas: U256::zero(),
+        base_gas: U256::zero(),
+        gas_price: U256::zero(),
+        gas_token: one,
+        refund_receiver: one,
+        nonce: U256::zero(),
+        operation: Operation::CALL,
+        safe_version: None,
+    };
+    let request = MultisigTransactionRequest::from(SignedSafePayload {
+        payload,
+        signature: Signature {
+            r: U256::from(1),
+            s: U256::from(2),
+            v: 27,
+        },
+        sender: "0x0000000000000000000000000000000000000002"
+            .parse()
+            .unwrap(),
+        origin: None,
+    });
+
+    // amounts are decimal strings and the operation a number, as the service expects
+    let expected = serde_json::json!({
+        "to": "0x0000000000000000000000000000000000000001",
+        "value": "0",
+        "data": format!("0x{}", "00".repeat(32)),
+        "nonce": "0",
+        "operation": 0,
+        "safeTxGas": "0",
+        "baseGas": "0",
+        "gasPrice": "0",
+        "gasToken": "0x0000000000000000000000000000000000000001",
+        "refundReceiver": "0x0000000000000000000000000000000000000001",
+        "safeTxHash": "0x0f7b372b07f04519dfa3c6e54766a16719474099fe10705fd5cd5567403134cd",
+        "sender": "0x0000000000000000000000000000000000000002",
+        "signature": format!("0x{:064x}{:064x}1b", 1, 2),
+    });
+    assert_eq!(serde_json::to_value(&request).unwrap(), expected);
+    assert_eq!(
+        serde_json::from_value::<MultisigTransactionRequest>(expected).unwrap(),
+        request
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_compatibility_report() {
+    let state = |implementation: &str, fallback_handler: serde_json::Value| {
+        serde_json::from_value::<SafeState>(serde_json::json!({
+            "address": { "value": "0x783c330A7A4968A08ce100A16ac27Ff2cCfAEbdf" },
+            "chainId": "1",
+            "nonce": 0,
+            "threshold": 1,
+            "owners": [{ "value": "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266" }],
+            "implementation": { "value": implementation },
+            "implementationVersionState": "UP_TO_DATE",
+            "modules": null,
+            "fallbackHandler": fallback_handler,
+            "guard": null,
+            "version": null,
+            "collectiblesTag": "0",
+            "txQueuedTag": "0",
+            "txHistoryTag": "0"
+        }))
+        .unwrap()
+    };
+    let singletons = SingletonTable::default();
+
+    let current = compatibility_report_for(
+        &state(
+            "0xd9Db270c1B5E3Bd161E8c8503c55cEABeE709552",
+            serde_json::json!({ "value": "0xf48f2B2d2a534e402487b3ee7C18c33Aec0Fe5e4" }),
+        ),
+        &singletons,
+    )
+    .unwrap();
+    assert_eq!(current.version, Some(SafeVersion::V1_3_0));
+    assert!(current.domain_includes_chain_id && current.supports_eip1271);
+    assert!(current.warnings.is_empty());
+
+    let legacy = compatibility_report_for(
+        &state(
+            "0x34CfAC646f301356fAa8B21e94227e3583Fe3F5F",
+            serde_json::Value::Null,
+        ),
+        &singletons,
+    )
+    .unwrap();
+    assert!(!legacy.domain_includes_chain_id && !legacy.supports_eip1271);
+    assert_eq!(legacy.warnings.len(), 2);
+    assert!(legacy.warnings[0].starts_with("v1.1.1: "));
+}
+
+#[cfg(test)]
+#[test]
+fn test_gas_estimate() {
+    let full: GasEstimate = serde_json::from_value(serde_json::json!({
+        "safeTxGas": "43000",
+        "baseGas": "0",
+        "gasPrice": 0,
+        "lastUsedNonce": 6
+    }))
+    .unwrap();
+    assert_eq!(full.safe_tx_gas, U256::from(43000));
+    assert_eq!(full.next_nonce(), 7);
+
+    let minimal: GasEstimate =
+        serde_json::from_value(serde_json::json!({ "safeTxGas": "43000" })).unwrap();
+    assert_eq!(minimal.last_used_nonce, None);
+    assert_eq!(minimal.next_nonce(), 0);
+}
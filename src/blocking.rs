@@ -0,0 +1,65 @@
+use crate::api;
+use crate::safe::SignedSafePayload;
+use crate::transaction::Transactionable;
+use ethers::types::{Address, U256};
+use lazy_static::lazy_static;
+use safe_client_gateway::routes::{
+    safes::models::SafeState,
+    transactions::models::{details::TransactionDetails, summary::TransactionSummary},
+};
+use tokio::runtime::{Builder, Runtime};
+
+lazy_static! {
+    /// Shared by every client and never dropped, as pooled connections are driven by the runtime
+    /// that opened them and fail once it is gone
+    static ref RUNTIME: Runtime = Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("blocking runtime to build");
+}
+
+/// The service calls of [api] for one chain, blocking on a process wide runtime like reqwest's
+/// blocking client
+///
+/// Panics if used from within an async runtime
+pub struct BlockingSafeClient {
+    chain_id: u64,
+}
+
+impl BlockingSafeClient {
+    pub fn new(chain_id: u64) -> anyhow::Result<Self> {
+        Ok(Self { chain_id })
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    pub fn safes(&self, address: Address) -> anyhow::Result<SafeState> {
+        RUNTIME.block_on(api::safes(self.chain_id, address))
+    }
+
+    pub fn queued(&self, address: Address) -> anyhow::Result<Vec<TransactionSummary>> {
+        RUNTIME.block_on(api::queued(self.chain_id, address))
+    }
+
+    /// The nonce the next transaction of the safe should use
+    pub fn next_nonce(&self, address: Address) -> anyhow::Result<U256> {
+        Ok(U256::from(self.safes(address)?.safe_config.nonce))
+    }
+
+    pub fn propose<T: Transactionable>(
+        &self,
+        tx: SignedSafePayload<T>,
+    ) -> anyhow::Result<TransactionDetails> {
+        if tx.payload.chain_id != self.chain_id {
+            anyhow::bail!(
+                "transaction is for chain {} but the client is for chain {}",
+                tx.payload.chain_id,
+                self.chain_id
+            );
+        }
+        RUNTIME.block_on(api::propose(tx))
+    }
+}
@@ -1,13 +1,17 @@
-use crate::transaction::Transactionable;
+use crate::encoding::{operation_from_u8, operation_to_u8};
+use crate::transaction::{Erc20Approve, Transactionable};
 use crate::types::Operation;
 use anyhow::Ok;
-use ethers::abi::Uint;
-use ethers::types::U256;
+use ethers::abi::{ParamType, Token, Uint};
+use ethers::types::{Address, U256};
 use std::ops::Add;
 
 /// multiSend(bytes memory transactions)"
 const SELECTOR_BYTES: &[u8] = &[141, 128, 255, 10];
 
+/// Length of the packed operation, to, value and data length preceding each call's data
+const PACKED_HEADER_LENGTH: usize = 1 + 20 + 32 + 32;
+
 #[derive(Debug, Clone)]
 pub struct Bundle<T: Transactionable> {
     pub transactions: Vec<T>,
@@ -38,10 +42,7 @@ impl<T: Transactionable> Bundle<T> {
 
                 let multisend_encoded_calldata = [
                     // 1 byte
-                    &[match op {
-                        Operation::CALL => 0 as u8,
-                        Operation::DELEGATE => 1 as u8,
-                    }],
+                    &[operation_to_u8(*op)],
                     // 20 bytes
                     tx.to().as_bytes(),
                     // 32 bytes
@@ -67,6 +68,19 @@ impl<T: Transactionable> Bundle<T> {
                 .fold(U256::zero(), |acc, value| acc.add(value)),
         })
     }
+
+    /// Bundles the transactions with their own [Transactionable::operation]
+    pub fn from_transactions(transactions: Vec<T>) -> anyhow::Result<Self> {
+        Self::new(
+            transactions
+                .into_iter()
+                .map(|tx| {
+                    let operation = tx.operation();
+                    (tx, operation)
+                })
+                .collect(),
+        )
+    }
 }
 
 fn abi_encode_uint(num: &U256) -> Vec<u8> {
@@ -85,6 +99,145 @@ impl<T: Transactionable> Transactionable for Bundle<T> {
     fn value(&self) -> U256 {
         self.value
     }
+
+    /// MultiSend has to be delegate called to make the calls from the safe
+    fn operation(&self) -> Operation {
+        Operation::DELEGATE
+    }
+}
+
+/// A single call unpacked from multiSend calldata
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSendCall {
+    pub operation: Operation,
+    pub to: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+impl Transactionable for MultiSendCall {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.data.clone())
+    }
+
+    fn to(&self) -> Address {
+        self.to
+    }
+
+    fn value(&self) -> U256 {
+        self.value
+    }
+
+    fn operation(&self) -> Operation {
+        self.operation
+    }
+}
+
+impl Bundle<MultiSendCall> {
+    /// Approves `spender` for `amount` of `token` then calls `target`, atomically
+    pub fn approve_and_call(
+        token: Address,
+        spender: Address,
+        amount: U256,
+        target: Address,
+        value: U256,
+        data: Vec<u8>,
+    ) -> anyhow::Result<Self> {
+        let approve = Erc20Approve {
+            token,
+            spender,
+            amount,
+        };
+        Self::from_transactions(vec![
+            MultiSendCall {
+                operation: Operation::CALL,
+                to: approve.to(),
+                value: approve.value(),
+                data: approve.calldata()?,
+            },
+            MultiSendCall {
+                operation: Operation::CALL,
+                to: target,
+                value,
+                data,
+            },
+        ])
+    }
+
+    /// Greedily packs `calls`, in order, into bundles whose summed `estimator` gas stays within
+    /// `max_gas`, each bundle is then proposed as its own transaction with its own nonce
+    ///
+    /// Errors if a single call is estimated above `max_gas`
+    pub fn chunk_by_gas(
+        calls: Vec<MultiSendCall>,
+        max_gas: u64,
+        estimator: impl Fn(&MultiSendCall) -> u64,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut chunks: Vec<Vec<MultiSendCall>> = vec![];
+        let mut chunk_gas = 0u64;
+        for call in calls {
+            let gas = estimator(&call);
+            if gas > max_gas {
+                anyhow::bail!(
+                    "call to {:?} needs {gas} gas, more than the limit of {max_gas}",
+                    call.to
+                );
+            }
+            match chunks.last_mut() {
+                Some(chunk) if chunk_gas.saturating_add(gas) <= max_gas => {
+                    chunk_gas += gas;
+                    chunk.push(call);
+                }
+                _ => {
+                    chunk_gas = gas;
+                    chunks.push(vec![call]);
+                }
+            }
+        }
+        chunks.into_iter().map(Self::from_transactions).collect()
+    }
+}
+
+/// Unpacks multiSend calldata back into its calls, see [Bundle::new] for the packed encoding
+pub fn decode_multisend(data: &[u8]) -> anyhow::Result<Vec<MultiSendCall>> {
+    if !data.starts_with(SELECTOR_BYTES) {
+        anyhow::bail!("not multiSend calldata");
+    }
+    let packed = match ethers::abi::decode(&[ParamType::Bytes], &data[4..])?.pop() {
+        Some(Token::Bytes(packed)) => packed,
+        _ => anyhow::bail!("multiSend calldata does not contain bytes"),
+    };
+
+    let mut calls = vec![];
+    let mut offset = 0;
+    while offset < packed.len() {
+        let header = packed
+            .get(offset..offset + PACKED_HEADER_LENGTH)
+            .ok_or_else(|| anyhow::anyhow!("truncated multiSend call at byte {offset}"))?;
+
+        let operation =
+            operation_from_u8(header[0]).map_err(|e| anyhow::anyhow!("{e} at byte {offset}"))?;
+        let data_length = U256::from_big_endian(&header[53..85]);
+        if data_length > U256::from(packed.len()) {
+            anyhow::bail!("data length {data_length} at byte {offset} runs past the buffer");
+        }
+
+        let start = offset + PACKED_HEADER_LENGTH;
+        let end = start + data_length.as_usize();
+        let data = packed
+            .get(start..end)
+            .ok_or_else(|| anyhow::anyhow!("data at byte {start} runs past the buffer"))?;
+
+        calls.push(MultiSendCall {
+            operation,
+            to: Address::from_slice(&header[1..21]),
+            value: U256::from_big_endian(&header[21..53]),
+            data: data.to_vec(),
+        });
+        offset = end;
+    }
+
+    Ok(calls)
 }
 
 #[cfg(test)]
@@ -117,3 +270,141 @@ fn test_encoding() {
     assert_eq!(encoded_operation.len(), 2);
     assert_eq!(len, String::from("1770"));
 }
+
+#[cfg(test)]
+#[test]
+fn test_decode_multisend() {
+    let calls = vec![
+        MultiSendCall {
+            operation: Operation::CALL,
+            to: "0x0000000000000000000000000000000000000001"
+                .parse()
+                .unwrap(),
+            value: U256::from(5),
+            data: vec![1, 2, 3],
+        },
+        MultiSendCall {
+            operation: Operation::DELEGATE,
+            to: "0x0000000000000000000000000000000000000002"
+                .parse()
+                .unwrap(),
+            value: U256::zero(),
+            data: vec![],
+        },
+    ];
+
+    let bundle = Bundle::new(
+        calls
+            .iter()
+            .map(|call| (call.clone(), call.operation))
+            .collect(),
+    )
+    .unwrap();
+    let calldata = bundle.calldata().unwrap();
+
+    assert_eq!(decode_multisend(&calldata).unwrap(), calls);
+    assert!(decode_multisend(&calldata[4..]).is_err());
+
+    // each call carries its own operation
+    let from_transactions = Bundle::from_transactions(calls).unwrap();
+    assert_eq!(from_transactions.calldata().unwrap(), calldata);
+
+    let multisend = |packed: Vec<u8>| {
+        let mut data = SELECTOR_BYTES.to_vec();
+        data.extend(ethers::abi::encode(&[Token::Bytes(packed)]));
+        data
+    };
+    // operation, to, value, then a data length of 3
+    let mut header = vec![0u8; PACKED_HEADER_LENGTH];
+    header[84] = 3;
+
+    let mut valid = header.clone();
+    valid.extend([1, 2, 3]);
+    assert_eq!(decode_multisend(&multisend(valid)).unwrap().len(), 1);
+
+    let truncated = header[..PACKED_HEADER_LENGTH - 1].to_vec();
+    assert!(decode_multisend(&multisend(truncated)).is_err());
+
+    let mut past_the_buffer = header.clone();
+    past_the_buffer.extend([1, 2]);
+    assert!(decode_multisend(&multisend(past_the_buffer)).is_err());
+
+    let mut bad_operation = header;
+    bad_operation[0] = 2;
+    bad_operation.extend([1, 2, 3]);
+    assert!(decode_multisend(&multisend(bad_operation)).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_approve_and_call() {
+    let token = Address::repeat_byte(1);
+    let spender = Address::repeat_byte(2);
+    let bundle = Bundle::approve_and_call(
+        token,
+        spender,
+        U256::from(100),
+        spender,
+        U256::from(3),
+        vec![4, 5],
+    )
+    .unwrap();
+
+    let manual = Bundle::new(vec![
+        (
+            crate::transaction::RawCall {
+                to: token,
+                value: U256::zero(),
+                data: Erc20Approve {
+                    token,
+                    spender,
+                    amount: U256::from(100),
+                }
+                .calldata()
+                .unwrap(),
+            },
+            Operation::CALL,
+        ),
+        (
+            crate::transaction::RawCall {
+                to: spender,
+                value: U256::from(3),
+                data: vec![4, 5],
+            },
+            Operation::CALL,
+        ),
+    ])
+    .unwrap();
+
+    assert_eq!(bundle.calldata().unwrap(), manual.calldata().unwrap());
+    assert_eq!(bundle.value(), U256::from(3));
+}
+
+#[cfg(test)]
+#[test]
+fn test_chunk_by_gas() {
+    let call = |gas: u8| MultiSendCall {
+        operation: Operation::CALL,
+        to: Address::repeat_byte(gas),
+        value: U256::zero(),
+        data: vec![],
+    };
+    let estimator = |call: &MultiSendCall| call.to.as_bytes()[0] as u64;
+
+    let chunks = Bundle::chunk_by_gas(
+        vec![call(40), call(50), call(20), call(90), call(10)],
+        100,
+        estimator,
+    )
+    .unwrap();
+    let gas: Vec<Vec<u64>> = chunks
+        .iter()
+        .map(|chunk| chunk.transactions.iter().map(estimator).collect())
+        .collect();
+    assert_eq!(gas, vec![vec![40, 50], vec![20], vec![90, 10]]);
+
+    assert!(Bundle::chunk_by_gas(vec![call(101)], 100, estimator).is_err());
+    assert!(Bundle::chunk_by_gas(vec![], 100, estimator)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+/// A network with a Safe deployment, unknown chain ids are kept as `Other`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Chain {
+    Ethereum,
+    Optimism,
+    Bsc,
+    Gnosis,
+    Polygon,
+    Base,
+    Arbitrum,
+    Avalanche,
+    Sepolia,
+    Other(u64),
+}
+
+impl Chain {
+    /// Every named chain, in chain id order
+    pub const KNOWN: &'static [Chain] = &[
+        Chain::Ethereum,
+        Chain::Optimism,
+        Chain::Bsc,
+        Chain::Gnosis,
+        Chain::Polygon,
+        Chain::Base,
+        Chain::Arbitrum,
+        Chain::Avalanche,
+        Chain::Sepolia,
+    ];
+
+    pub fn as_u64(&self) -> u64 {
+        match self {
+            Chain::Ethereum => 1,
+            Chain::Optimism => 10,
+            Chain::Bsc => 56,
+            Chain::Gnosis => 100,
+            Chain::Polygon => 137,
+            Chain::Base => 8453,
+            Chain::Arbitrum => 42161,
+            Chain::Avalanche => 43114,
+            Chain::Sepolia => 11155111,
+            Chain::Other(chain_id) => *chain_id,
+        }
+    }
+
+    pub fn from_u64(chain_id: u64) -> Self {
+        Self::KNOWN
+            .iter()
+            .find(|chain| chain.as_u64() == chain_id)
+            .copied()
+            .unwrap_or(Chain::Other(chain_id))
+    }
+
+    /// The EIP-3770 short name, e.g. `eth` in `eth:0x...`
+    pub fn short_name(&self) -> Option<&'static str> {
+        match self {
+            Chain::Ethereum => Some("eth"),
+            Chain::Optimism => Some("oeth"),
+            Chain::Bsc => Some("bnb"),
+            Chain::Gnosis => Some("gno"),
+            Chain::Polygon => Some("matic"),
+            Chain::Base => Some("base"),
+            Chain::Arbitrum => Some("arb1"),
+            Chain::Avalanche => Some("avax"),
+            Chain::Sepolia => Some("sep"),
+            Chain::Other(_) => None,
+        }
+    }
+
+    pub fn from_short_name(short_name: &str) -> Option<Self> {
+        Self::KNOWN
+            .iter()
+            .find(|chain| chain.short_name() == Some(short_name))
+            .copied()
+    }
+
+    /// The host of the chain's transaction service run by Safe
+    pub fn tx_service_host(&self) -> Option<&'static str> {
+        match self {
+            Chain::Ethereum => Some("safe-transaction-mainnet.safe.global"),
+            Chain::Optimism => Some("safe-transaction-optimism.safe.global"),
+            Chain::Bsc => Some("safe-transaction-bsc.safe.global"),
+            Chain::Gnosis => Some("safe-transaction-gnosis-chain.safe.global"),
+            Chain::Polygon => Some("safe-transaction-polygon.safe.global"),
+            Chain::Base => Some("safe-transaction-base.safe.global"),
+            Chain::Arbitrum => Some("safe-transaction-arbitrum.safe.global"),
+            Chain::Avalanche => Some("safe-transaction-avalanche.safe.global"),
+            Chain::Sepolia => Some("safe-transaction-sepolia.safe.global"),
+            Chain::Other(_) => None,
+        }
+    }
+
+    /// The symbol of the native currency, assumed ETH on chains that are not named
+    pub fn native_symbol(&self) -> &'static str {
+        match self {
+            Chain::Bsc => "BNB",
+            Chain::Gnosis => "XDAI",
+            Chain::Polygon => "POL",
+            Chain::Avalanche => "AVAX",
+            _ => "ETH",
+        }
+    }
+}
+
+impl From<u64> for Chain {
+    fn from(chain_id: u64) -> Self {
+        Self::from_u64(chain_id)
+    }
+}
+
+impl From<Chain> for u64 {
+    fn from(chain: Chain) -> Self {
+        chain.as_u64()
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_u64())
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_chain_round_trip() {
+    for chain in Chain::KNOWN {
+        assert_eq!(Chain::from_u64(chain.as_u64()), *chain);
+        assert_eq!(
+            Chain::from_short_name(chain.short_name().unwrap()),
+            Some(*chain)
+        );
+    }
+    assert_eq!(Chain::from_u64(31337), Chain::Other(31337));
+    assert_eq!(Chain::Other(31337).short_name(), None);
+}
@@ -0,0 +1,77 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The time source of the polling helpers, swap it out to test them without waiting
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()>;
+}
+
+/// Wall clock time and tokio timers, what the helpers without a clock argument use
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        tokio::time::sleep(duration).boxed()
+    }
+}
+
+/// A clock that only moves when slept on or advanced, sleeping returns immediately
+#[derive(Debug)]
+pub struct ManualClock {
+    origin: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+
+    /// Total time slept or advanced so far
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        self.advance(duration);
+        futures::future::ready(()).boxed()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_manual_clock() {
+    let clock = ManualClock::new();
+    let start = clock.now();
+    futures::executor::block_on(clock.sleep(Duration::from_secs(30)));
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(clock.now() - start, Duration::from_secs(35));
+    assert_eq!(clock.elapsed(), Duration::from_secs(35));
+}
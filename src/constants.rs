@@ -6,6 +6,21 @@ lazy_static! {
         .parse()
         .unwrap();
 
+    /// The AllowanceModule v0.1.0, deployed to the same address on most chains
+    pub static ref ALLOWANCE_MODULE_ADDRESS: Address = "0xCFbFaC74C26F8647cBDb8c5caf80BB5b32E43134"
+        .parse()
+        .unwrap();
+
+    /// The AllowanceModule v0.1.1, the only version on chains launched after v0.1.0
+    pub static ref ALLOWANCE_MODULE_V0_1_1_ADDRESS: Address = "0xAA46724893dedD72658219405185Fb0Fc91e091C"
+        .parse()
+        .unwrap();
+
+    /// The head of the owner and module linked lists in the safe contract
+    pub static ref SENTINEL_ADDRESS: Address = "0x0000000000000000000000000000000000000001"
+        .parse()
+        .unwrap();
+
     // keccak256(
     //     "EIP712Domain(uint256 chainId,address verifyingContract)"
     // );
@@ -14,6 +29,15 @@ lazy_static! {
         168, 197, 115, 243, 216, 3, 175, 185, 42, 121, 70, 146, 24,
     ];
 
+    // keccak256(
+    //     "EIP712Domain(address verifyingContract)"
+    // );
+    // used by safes before v1.3.0
+    pub static ref LEGACY_DOMAIN_TYPE_HASH: Vec<u8> = vec![
+        3, 90, 255, 131, 216, 105, 55, 211, 91, 50, 224, 79, 13, 220, 111, 244, 105, 41, 14, 239,
+        47, 27, 105, 45, 138, 129, 92, 137, 64, 77, 71, 73,
+    ];
+
     // keccak256(
     //     "SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)"
     // );
@@ -21,4 +45,28 @@ lazy_static! {
         187, 131, 16, 212, 134, 54, 141, 182, 189, 111, 132, 148, 2, 253, 215, 58, 213, 61, 49,
         107, 90, 75, 38, 68, 173, 110, 254, 15, 148, 18, 134, 216,
     ];
+
+    // keccak256(
+    //     "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+    // );
+    pub static ref SERVICE_DOMAIN_TYPE_HASH: Vec<u8> = vec![
+        139, 115, 195, 198, 155, 184, 254, 61, 81, 46, 204, 76, 247, 89, 204, 121, 35, 159, 123,
+        23, 155, 15, 250, 202, 169, 167, 93, 82, 43, 57, 64, 15,
+    ];
+
+    // keccak256(
+    //     "DeleteRequest(bytes32 safeTxHash,uint256 totp)"
+    // );
+    pub static ref DELETE_REQUEST_TYPE_HASH: Vec<u8> = vec![
+        234, 14, 41, 32, 174, 148, 255, 219, 157, 152, 183, 187, 184, 91, 195, 79, 228, 223, 92,
+        204, 163, 169, 138, 46, 59, 254, 200, 182, 73, 25, 252, 8,
+    ];
+
+    // keccak256(
+    //     "SafeMessage(bytes message)"
+    // );
+    pub static ref SAFE_MESSAGE_TYPE_HASH: Vec<u8> = vec![
+        96, 179, 203, 248, 180, 162, 35, 214, 141, 100, 27, 59, 109, 223, 154, 41, 142, 127, 51,
+        113, 12, 243, 211, 169, 209, 20, 107, 90, 97, 80, 251, 202,
+    ];
 }
@@ -0,0 +1,150 @@
+use ethers::abi::{AbiParser, Function, Token};
+use ethers::types::Address;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref DEFAULT_DECODER: ActionDecoder = ActionDecoder::default();
+}
+
+/// Selectors recognized by default, as (label, human readable signature)
+const KNOWN_ACTIONS: &[(&str, &str)] = &[
+    ("ERC20 transfer", "transfer(address to, uint256 amount)"),
+    ("ERC20 approve", "approve(address spender, uint256 amount)"),
+    (
+        "Safe add owner",
+        "addOwnerWithThreshold(address owner, uint256 threshold)",
+    ),
+    (
+        "Safe change threshold",
+        "changeThreshold(uint256 threshold)",
+    ),
+    ("Safe enable module", "enableModule(address module)"),
+    ("MultiSend", "multiSend(bytes transactions)"),
+];
+
+/// A call recognized by its selector, with its decoded parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAction {
+    pub to: Address,
+    pub label: String,
+    pub method: String,
+    /// (name, value) in declaration order
+    pub params: Vec<(String, Token)>,
+}
+
+/// A table of recognized selectors, the default one knows `KNOWN_ACTIONS`
+#[derive(Debug, Clone)]
+pub struct ActionDecoder {
+    actions: Vec<(String, Function)>,
+}
+
+impl ActionDecoder {
+    pub fn empty() -> Self {
+        Self { actions: vec![] }
+    }
+
+    /// Recognize another selector, e.g. `register("Deposit", "deposit(uint256 amount)")`
+    pub fn register(mut self, label: &str, signature: &str) -> anyhow::Result<Self> {
+        let function = AbiParser::default().parse_function(signature)?;
+        self.actions.push((label.to_owned(), function));
+        Ok(self)
+    }
+
+    /// Returns None for unknown selectors or calldata that doesn't decode
+    pub fn decode(&self, to: Address, data: &[u8]) -> Option<DecodedAction> {
+        if data.len() < 4 {
+            return None;
+        }
+        let (label, function) = self
+            .actions
+            .iter()
+            .find(|(_, function)| function.short_signature() == data[..4])?;
+        let tokens = function.decode_input(&data[4..]).ok()?;
+
+        Some(DecodedAction {
+            to,
+            label: label.clone(),
+            method: function.name.clone(),
+            params: function
+                .inputs
+                .iter()
+                .map(|param| param.name.clone())
+                .zip(tokens)
+                .collect(),
+        })
+    }
+}
+
+impl Default for ActionDecoder {
+    fn default() -> Self {
+        KNOWN_ACTIONS
+            .iter()
+            .try_fold(Self::empty(), |decoder, (label, signature)| {
+                decoder.register(label, signature)
+            })
+            .expect("known actions to parse")
+    }
+}
+
+/// Decodes calldata against the default table of known selectors
+pub fn decode_action(to: Address, data: &[u8]) -> Option<DecodedAction> {
+    DEFAULT_DECODER.decode(to, data)
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_transfer() {
+    use ethers::types::U256;
+
+    let token = Address::repeat_byte(1);
+    let recipient = Address::repeat_byte(2);
+    let mut data = hex::decode("a9059cbb").unwrap();
+    data.extend(ethers::abi::encode(&[
+        Token::Address(recipient),
+        Token::Uint(U256::from(1000)),
+    ]));
+
+    let action = decode_action(token, &data).unwrap();
+    assert_eq!(action.to, token);
+    assert_eq!(action.label, "ERC20 transfer");
+    assert_eq!(action.method, "transfer");
+    assert_eq!(
+        action.params,
+        vec![
+            ("to".to_owned(), Token::Address(recipient)),
+            ("amount".to_owned(), Token::Uint(U256::from(1000))),
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_registered() {
+    use ethers::types::U256;
+
+    let decoder = ActionDecoder::default()
+        .register("Deposit", "deposit(uint256 amount)")
+        .unwrap();
+    let mut data = ethers::utils::id("deposit(uint256)").to_vec();
+    data.extend(ethers::abi::encode(&[Token::Uint(U256::from(7))]));
+
+    let action = decoder.decode(Address::zero(), &data).unwrap();
+    assert_eq!(action.label, "Deposit");
+    assert_eq!(
+        action.params,
+        vec![("amount".to_owned(), Token::Uint(U256::from(7)))]
+    );
+    // the default table doesn't know it
+    assert_eq!(decode_action(Address::zero(), &data), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_unknown() {
+    let to = Address::zero();
+    assert_eq!(decode_action(to, &[]), None);
+    assert_eq!(decode_action(to, &[0xa9, 0x05, 0x9c]), None);
+    assert_eq!(decode_action(to, &[0xde, 0xad, 0xbe, 0xef, 0, 0]), None);
+    // a known selector with truncated arguments
+    assert_eq!(decode_action(to, &[0xa9, 0x05, 0x9c, 0xbb, 0, 0]), None);
+}
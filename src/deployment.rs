@@ -0,0 +1,272 @@
+use crate::encoding::encode_function_call;
+use crate::transaction::Transactionable;
+use ethers::abi::{self, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::{get_create2_address, keccak256};
+
+/// The `setup` call a new safe proxy is initialized with, with no payment and no setup delegatecall
+pub fn setup_calldata(owners: &[Address], threshold: u32, fallback_handler: Address) -> Vec<u8> {
+    encode_function_call(
+        "setup(address[],uint256,address,bytes,address,address,uint256,address)",
+        &[
+            Token::Array(owners.iter().copied().map(Token::Address).collect()),
+            Token::Uint(U256::from(threshold)),
+            Token::Address(Address::zero()),
+            Token::Bytes(vec![]),
+            Token::Address(fallback_handler),
+            Token::Address(Address::zero()),
+            Token::Uint(U256::zero()),
+            Token::Address(Address::zero()),
+        ],
+    )
+}
+
+/// Reproduces the CREATE2 address of `createProxyWithNonce` on the ProxyFactory
+///
+/// `proxy_creation_code` is the factory's `proxyCreationCode()`, it differs between factory versions
+pub fn predict_safe_address(
+    factory: Address,
+    singleton: Address,
+    owners: &[Address],
+    threshold: u32,
+    salt_nonce: U256,
+    fallback_handler: Address,
+    proxy_creation_code: &[u8],
+) -> Address {
+    let initializer = setup_calldata(owners, threshold, fallback_handler);
+    predict_proxy_address(
+        factory,
+        singleton,
+        &initializer,
+        salt_nonce,
+        proxy_creation_code,
+    )
+}
+
+fn predict_proxy_address(
+    factory: Address,
+    singleton: Address,
+    initializer: &[u8],
+    salt_nonce: U256,
+    proxy_creation_code: &[u8],
+) -> Address {
+    // keccak256(abi.encodePacked(keccak256(initializer), saltNonce))
+    let salt = keccak256(
+        [
+            &keccak256(initializer)[..],
+            &abi::encode(&[Token::Uint(salt_nonce)]),
+        ]
+        .concat(),
+    );
+    // abi.encodePacked(proxyCreationCode, uint256(uint160(singleton)))
+    let init_code = [
+        proxy_creation_code,
+        &abi::encode(&[Token::Address(singleton)]),
+    ]
+    .concat();
+
+    get_create2_address(factory, salt.to_vec(), init_code)
+}
+
+/// Defaults to no fallback handler and a salt nonce of zero
+#[derive(Debug, Clone)]
+pub struct SafeDeploymentBuilder {
+    pub factory: Address,
+    pub singleton: Address,
+    pub owners: Vec<Address>,
+    pub threshold: u32,
+    pub proxy_creation_code: Vec<u8>,
+    pub salt_nonce: Option<U256>,
+    pub fallback_handler: Option<Address>,
+}
+
+/// A `createProxyWithNonce(singleton, initializer, saltNonce)` call on the ProxyFactory
+#[derive(Debug, Clone)]
+pub struct SafeDeployment {
+    pub factory: Address,
+    pub singleton: Address,
+    pub initializer: Vec<u8>,
+    pub salt_nonce: U256,
+}
+
+impl SafeDeploymentBuilder {
+    pub fn new(
+        factory: Address,
+        singleton: Address,
+        owners: Vec<Address>,
+        threshold: u32,
+        proxy_creation_code: Vec<u8>,
+    ) -> Self {
+        Self {
+            factory,
+            singleton,
+            owners,
+            threshold,
+            proxy_creation_code,
+            salt_nonce: None,
+            fallback_handler: None,
+        }
+    }
+
+    pub fn salt_nonce(mut self, salt_nonce: U256) -> Self {
+        self.salt_nonce = Some(salt_nonce);
+        self
+    }
+
+    pub fn fallback_handler(mut self, fallback_handler: Address) -> Self {
+        self.fallback_handler = Some(fallback_handler);
+        self
+    }
+
+    /// Returns the deployment transaction and the address the safe will be deployed at
+    pub fn build(self) -> anyhow::Result<(SafeDeployment, Address)> {
+        if self.owners.is_empty() {
+            anyhow::bail!("a safe needs at least one owner");
+        }
+        if self.threshold == 0 || self.threshold as usize > self.owners.len() {
+            anyhow::bail!(
+                "threshold {} is invalid for {} owners",
+                self.threshold,
+                self.owners.len()
+            );
+        }
+
+        let initializer = setup_calldata(
+            &self.owners,
+            self.threshold,
+            self.fallback_handler.unwrap_or(Address::zero()),
+        );
+        let salt_nonce = self.salt_nonce.unwrap_or(U256::zero());
+        let address = predict_proxy_address(
+            self.factory,
+            self.singleton,
+            &initializer,
+            salt_nonce,
+            &self.proxy_creation_code,
+        );
+
+        Ok((
+            SafeDeployment {
+                factory: self.factory,
+                singleton: self.singleton,
+                initializer,
+                salt_nonce,
+            },
+            address,
+        ))
+    }
+}
+
+impl Transactionable for SafeDeployment {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(encode_function_call(
+            "createProxyWithNonce(address,bytes,uint256)",
+            &[
+                Token::Address(self.singleton),
+                Token::Bytes(self.initializer.clone()),
+                Token::Uint(self.salt_nonce),
+            ],
+        ))
+    }
+
+    fn to(&self) -> Address {
+        self.factory
+    }
+
+    fn value(&self) -> U256 {
+        U256::zero()
+    }
+}
+
+/// `proxyCreationCode()` of the v1.3.0 ProxyFactory at 0xa6B71E26C5e0845f74c812102Ca7114b6a896AB2
+#[cfg(test)]
+const PROXY_CREATION_CODE_V1_3_0: &str = concat!(
+    "608060405234801561001057600080fd5b506040516101e63803806101e68339818101604052602081101561",
+    "003357600080fd5b8101908080519060200190929190505050600073ffffffffffffffffffffffffffffffff",
+    "ffffffff168173ffffffffffffffffffffffffffffffffffffffff1614156100ca576040517f08c379a00000",
+    "0000000000000000000000000000000000000000000000000000815260040180806020018281038252602281",
+    "52602001806101c46022913960400191505060405180910390fd5b806000806101000a81548173ffffffffff",
+    "ffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff160217",
+    "9055505060ab806101196000396000f3fe608060405273ffffffffffffffffffffffffffffffffffffffff60",
+    "0054167fa619486e000000000000000000000000000000000000000000000000000000006000351415605057",
+    "8060005260206000f35b3660008037600080366000845af43d6000803e60008114156070573d6000fd5b3d60",
+    "00f3fea2646970667358221220d1429297349653a4918076d650332de1a1068c5f3e07c5c82360c277770b95",
+    "5264736f6c63430007060033496e76616c69642073696e676c65746f6e20616464726573732070726f766964",
+    "6564",
+);
+
+#[cfg(test)]
+fn address(s: &str) -> Address {
+    s.parse().unwrap()
+}
+
+#[cfg(test)]
+#[test]
+fn test_predict_safe_address() {
+    // the mainnet v1.3.0 factory, singleton and compatibility fallback handler, the expected
+    // address is computed from the CREATE2 formula outside of this crate
+    let predicted = predict_safe_address(
+        address("0xa6B71E26C5e0845f74c812102Ca7114b6a896AB2"),
+        address("0xd9Db270c1B5E3Bd161E8c8503c55cEABeE709552"),
+        &[
+            address("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"),
+            address("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+        ],
+        2,
+        U256::from(1_700_000_000u64),
+        address("0xf48f2B2d2a534e402487b3ee7C18c33Aec0Fe5e4"),
+        &hex::decode(PROXY_CREATION_CODE_V1_3_0).unwrap(),
+    );
+    assert_eq!(
+        predicted,
+        address("0x17653469b404e878c26fb1b31cd7bc382eb92a65")
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_deployment_builder() {
+    let factory = address("0xa6B71E26C5e0845f74c812102Ca7114b6a896AB2");
+    let singleton = address("0xd9Db270c1B5E3Bd161E8c8503c55cEABeE709552");
+    let handler = address("0xf48f2B2d2a534e402487b3ee7C18c33Aec0Fe5e4");
+    let owners = vec![
+        address("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"),
+        address("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+    ];
+    let code = hex::decode(PROXY_CREATION_CODE_V1_3_0).unwrap();
+    let builder = |owners: Vec<Address>, threshold| {
+        SafeDeploymentBuilder::new(factory, singleton, owners, threshold, code.clone())
+    };
+
+    assert!(builder(vec![], 1).build().is_err());
+    assert!(builder(owners.clone(), 0).build().is_err());
+    assert!(builder(owners.clone(), 3).build().is_err());
+
+    let (deployment, predicted) = builder(owners.clone(), 2)
+        .salt_nonce(U256::from(1_700_000_000u64))
+        .fallback_handler(handler)
+        .build()
+        .unwrap();
+    assert_eq!(deployment.to(), factory);
+    assert_eq!(
+        predicted,
+        predict_safe_address(
+            factory,
+            singleton,
+            &owners,
+            2,
+            U256::from(1_700_000_000u64),
+            handler,
+            &code
+        )
+    );
+
+    let calldata = deployment.calldata().unwrap();
+    // createProxyWithNonce(address,bytes,uint256)
+    assert_eq!(calldata[..4], [0x16, 0x88, 0xf0, 0xb9]);
+    assert_eq!(calldata.len(), 548);
+    assert_eq!(
+        hex::encode(keccak256(&calldata)),
+        "8cf5dd747a75150c6280b7980a09a01c1cdcf8c3c8a8fa8503e1fbb8b5bf842f"
+    );
+}
@@ -0,0 +1,182 @@
+use crate::chain::Chain;
+use crate::decode::decode_action;
+use crate::transaction::Transactionable;
+use ethers::abi::Token;
+use ethers::types::{Address, U256};
+use ethers::utils::{format_units, to_checksum};
+use std::collections::HashMap;
+
+/// The symbol and decimals used to display amounts of a token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+/// Labels for addresses and metadata for tokens, shown instead of raw addresses
+///
+/// Keyed by address, so any casing of an address finds the same entry
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    labels: HashMap<Address, String>,
+    tokens: HashMap<Address, TokenMetadata>,
+    native_symbol: Option<String>,
+}
+
+impl AddressBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows native amounts in the chain's currency rather than ETH
+    pub fn for_chain(chain: Chain) -> Self {
+        Self::new().with_native_symbol(chain.native_symbol())
+    }
+
+    pub fn with_native_symbol(mut self, symbol: &str) -> Self {
+        self.native_symbol = Some(symbol.to_owned());
+        self
+    }
+
+    pub fn with_label(mut self, address: Address, label: &str) -> Self {
+        self.labels.insert(address, label.to_owned());
+        self
+    }
+
+    pub fn with_token(mut self, token: Address, symbol: &str, decimals: u32) -> Self {
+        self.tokens.insert(
+            token,
+            TokenMetadata {
+                symbol: symbol.to_owned(),
+                decimals,
+            },
+        );
+        self
+    }
+
+    pub fn label(&self, address: Address) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str)
+    }
+
+    pub fn token(&self, token: Address) -> Option<&TokenMetadata> {
+        self.tokens.get(&token)
+    }
+
+    /// ETH unless set with [Self::for_chain] or [Self::with_native_symbol]
+    pub fn native_symbol(&self) -> &str {
+        self.native_symbol.as_deref().unwrap_or("ETH")
+    }
+
+    /// The label of `address`, or its checksummed form when unknown
+    pub fn name(&self, address: Address) -> String {
+        self.label(address)
+            .map(str::to_owned)
+            .or_else(|| self.token(address).map(|token| token.symbol.clone()))
+            .unwrap_or_else(|| to_checksum(&address, None))
+    }
+
+    /// e.g. "100 USDC", or the raw amount followed by the token's name without metadata
+    pub fn amount(&self, token: Address, amount: U256) -> String {
+        match self.token(token) {
+            Some(metadata) => format!(
+                "{} {}",
+                format_amount(amount, metadata.decimals),
+                metadata.symbol
+            ),
+            None => format!("{amount} of {}", self.name(token)),
+        }
+    }
+}
+
+/// Formats without trailing zeros, e.g. 1.5 rather than 1.500000
+fn format_amount(amount: U256, decimals: u32) -> String {
+    match format_units(amount, decimals) {
+        Ok(formatted) if formatted.contains('.') => formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_owned(),
+        Ok(formatted) => formatted,
+        Err(_) => amount.to_string(),
+    }
+}
+
+/// A one line summary of a transaction such as "Transfer 100 USDC to Treasury-Ops"
+pub fn describe_transaction<T: Transactionable>(tx: &T, book: &AddressBook) -> String {
+    let to = tx.to();
+    let data = match tx.calldata() {
+        Ok(data) => data,
+        Err(e) => return format!("Call {} with unencodable data: {e}", book.name(to)),
+    };
+    if data.is_empty() {
+        return format!(
+            "Send {} {} to {}",
+            format_amount(tx.value(), 18),
+            book.native_symbol(),
+            book.name(to)
+        );
+    }
+
+    let action = match decode_action(to, &data) {
+        Some(action) => action,
+        None => return format!("Call {} with {} bytes of data", book.name(to), data.len()),
+    };
+    let params: Vec<&Token> = action.params.iter().map(|(_, token)| token).collect();
+    match (action.method.as_str(), &params[..]) {
+        ("transfer", [Token::Address(recipient), Token::Uint(amount)]) => format!(
+            "Transfer {} to {}",
+            book.amount(to, *amount),
+            book.name(*recipient)
+        ),
+        ("approve", [Token::Address(spender), Token::Uint(amount)]) => format!(
+            "Approve {} to spend {}",
+            book.name(*spender),
+            book.amount(to, *amount)
+        ),
+        _ => format!("{} ({}) on {}", action.label, action.method, book.name(to)),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_describe_transaction() {
+    use crate::transaction::{Erc20Transfer, EtherTransfer};
+
+    let usdc: Address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+        .parse()
+        .unwrap();
+    let treasury: Address = "0x000000000000000000000000000000000000dEaD"
+        .parse()
+        .unwrap();
+    let book = AddressBook::new()
+        .with_token(usdc, "USDC", 6)
+        .with_label(treasury, "Treasury-Ops");
+
+    let transfer = Erc20Transfer {
+        token: usdc,
+        to: treasury,
+        amount: U256::from(100_000_000),
+    };
+    assert_eq!(
+        describe_transaction(&transfer, &book),
+        "Transfer 100 USDC to Treasury-Ops"
+    );
+
+    let send = EtherTransfer {
+        to: Address::repeat_byte(0xab),
+        amount: U256::exp10(18) * 3 / 2,
+    };
+    assert_eq!(
+        describe_transaction(&send, &book),
+        format!(
+            "Send 1.5 ETH to {}",
+            to_checksum(&Address::repeat_byte(0xab), None)
+        )
+    );
+    assert_eq!(
+        describe_transaction(&send, &AddressBook::for_chain(Chain::Gnosis)),
+        format!(
+            "Send 1.5 XDAI to {}",
+            to_checksum(&Address::repeat_byte(0xab), None)
+        )
+    );
+}
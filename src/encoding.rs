@@ -1,3 +1,10 @@
+use crate::chain::Chain;
+use ethers::abi::Token;
+use ethers::types::{Address, U256};
+use ethers::utils::to_checksum;
+use safe_client_gateway::common::models::data_decoded::Operation;
+use serde::{Deserialize, Deserializer};
+
 pub fn hex_string_to_bytes(hex: &str) -> anyhow::Result<Vec<u8>> {
     Ok(hex::decode(hex.replace("0x", ""))?)
 }
@@ -5,3 +12,143 @@ pub fn hex_string_to_bytes(hex: &str) -> anyhow::Result<Vec<u8>> {
 pub fn bytes_to_hex_string<T: AsRef<[u8]>>(bytes: T) -> String {
     hex::encode(bytes)
 }
+
+/// The 4 byte selector of `signature` followed by the abi encoded arguments
+pub fn encode_function_call(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    [
+        &ethers::utils::id(signature)[..],
+        &ethers::abi::encode(tokens),
+    ]
+    .concat()
+}
+
+/// The raw value of an operation as used in calldata, 0 for CALL and 1 for DELEGATE
+pub fn operation_to_u8(operation: Operation) -> u8 {
+    match operation {
+        Operation::CALL => 0,
+        Operation::DELEGATE => 1,
+    }
+}
+
+pub fn operation_from_u8(operation: u8) -> anyhow::Result<Operation> {
+    match operation {
+        0 => Ok(Operation::CALL),
+        1 => Ok(Operation::DELEGATE),
+        _ => anyhow::bail!("invalid operation {operation}"),
+    }
+}
+
+/// Deserializes an optional address, treating the zero address the same as null
+pub fn deserialize_zero_address_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let address = Option::<String>::deserialize(deserializer)?;
+    Ok(address.filter(|address| {
+        address
+            .parse::<Address>()
+            .map_or(true, |address| !address.is_zero())
+    }))
+}
+
+/// Deserializes a uint the service sends as a decimal string, or as a plain number
+pub fn deserialize_decimal_u256<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Decimal {
+        String(String),
+        Number(u64),
+    }
+
+    match Decimal::deserialize(deserializer)? {
+        Decimal::String(decimal) => U256::from_dec_str(&decimal).map_err(serde::de::Error::custom),
+        Decimal::Number(number) => Ok(U256::from(number)),
+    }
+}
+
+/// Compares an address against a string in any casing, with or without `0x`
+///
+/// Strings that aren't addresses compare unequal
+pub fn address_eq_str(address: &Address, s: &str) -> bool {
+    s.trim()
+        .parse::<Address>()
+        .map_or(false, |parsed| parsed == *address)
+}
+
+/// Chains whose addresses are checksummed with their chain id, as in EIP-1191
+const EIP1191_CHAINS: &[u64] = &[30, 31];
+
+/// The EIP-55 checksum, or the EIP-1191 one on chains that use it (RSK mainnet and testnet)
+pub fn checksum_for_chain(address: &Address, chain_id: u64) -> String {
+    if EIP1191_CHAINS.contains(&chain_id) {
+        to_checksum(address, Some(chain_id as u8))
+    } else {
+        to_checksum(address, None)
+    }
+}
+
+/// Parses an EIP-3770 chain prefixed address such as `eth:0x...` into its chain id and address
+pub fn parse_eip3770(s: &str) -> anyhow::Result<(u64, Address)> {
+    let (short_name, address) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("{s} has no chain prefix"))?;
+    let chain = Chain::from_short_name(short_name)
+        .ok_or_else(|| anyhow::anyhow!("unknown chain short name {short_name}"))?;
+    Ok((chain.as_u64(), address.parse()?))
+}
+
+/// Formats a checksummed address with the EIP-3770 short name of its chain
+pub fn to_eip3770(address: &Address, chain_id: u64) -> anyhow::Result<String> {
+    let short_name = Chain::from_u64(chain_id)
+        .short_name()
+        .ok_or_else(|| anyhow::anyhow!("no short name known for chain {chain_id}"))?;
+    Ok(format!("{short_name}:{}", to_checksum(address, None)))
+}
+
+#[cfg(test)]
+#[test]
+fn test_checksum_for_chain() {
+    let address: Address = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        checksum_for_chain(&address, 1),
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+    );
+    assert_eq!(
+        checksum_for_chain(&address, 30),
+        "0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_address_eq_str() {
+    let address: Address = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        .parse()
+        .unwrap();
+    assert!(address_eq_str(
+        &address,
+        "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+    ));
+    assert!(address_eq_str(
+        &address,
+        "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"
+    ));
+    assert!(address_eq_str(
+        &address,
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+    ));
+    assert!(address_eq_str(
+        &address,
+        "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+    ));
+    assert!(!address_eq_str(
+        &address,
+        "0x0000000000000000000000000000000000000001"
+    ));
+    assert!(!address_eq_str(&address, "Treasury-Ops"));
+}
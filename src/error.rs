@@ -0,0 +1,118 @@
+use ethers::types::{Address, H256};
+use ethers::utils::to_checksum;
+use std::fmt;
+use std::time::Duration;
+
+/// Errors worth matching on, returned inside `anyhow::Error` so use `downcast_ref` to inspect them
+#[derive(Debug)]
+pub enum SafeError {
+    /// The service doesn't know a safe at this address on the requested chain
+    SafeNotFound(Address),
+    /// The response body exceeded the configured limit of this many bytes
+    ResponseTooLarge(usize),
+    /// A contract or approved hash signature of this owner, which has no signer to recover
+    NonEcdsaSignature(Address),
+    /// Waiting on the service gave up after this long
+    Timeout(Duration),
+    /// The transaction was executed in this ethereum transaction but reverted
+    ExecutionFailed(H256),
+    /// Another transaction with the same nonce was executed instead
+    ExecutionCancelled,
+}
+
+impl fmt::Display for SafeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SafeError::SafeNotFound(address) => write!(
+                f,
+                "{} is not a safe known to the service on this chain",
+                to_checksum(address, None)
+            ),
+            SafeError::ResponseTooLarge(limit) => {
+                write!(f, "response body exceeded the limit of {limit} bytes")
+            }
+            SafeError::NonEcdsaSignature(owner) => write!(
+                f,
+                "the signature of {} is a contract or approved hash signature",
+                to_checksum(owner, None)
+            ),
+            SafeError::Timeout(timeout) => write!(f, "timed out after {timeout:?}"),
+            SafeError::ExecutionFailed(tx_hash) => write!(f, "execution failed in {tx_hash:?}"),
+            SafeError::ExecutionCancelled => {
+                write!(f, "the transaction was cancelled by another with its nonce")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SafeError {}
+
+/// The body of a 422 from the service, each rejected field with its messages
+///
+/// Errors not tied to a field come under `nonFieldErrors` (or `non_field_errors`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceValidationError {
+    pub errors: Vec<(String, Vec<String>)>,
+}
+
+impl ServiceValidationError {
+    /// None if the body isn't a JSON object of fields to lists of messages
+    pub fn parse(body: &str) -> Option<Self> {
+        let fields: serde_json::Map<String, serde_json::Value> = serde_json::from_str(body).ok()?;
+        let errors = fields
+            .into_iter()
+            .map(|(field, messages)| {
+                let messages = match messages {
+                    serde_json::Value::String(message) => vec![message],
+                    messages => serde_json::from_value(messages).ok()?,
+                };
+                Some((field, messages))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        (!errors.is_empty()).then_some(Self { errors })
+    }
+
+    /// The messages for `field`, empty if it wasn't rejected
+    pub fn messages(&self, field: &str) -> &[String] {
+        self.errors
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, messages)| messages.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ServiceValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the service rejected the request")?;
+        for (field, messages) in &self.errors {
+            match field.as_str() {
+                "nonFieldErrors" | "non_field_errors" => write!(f, "; {}", messages.join(", "))?,
+                _ => write!(f, "; {field}: {}", messages.join(", "))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ServiceValidationError {}
+
+#[cfg(test)]
+#[test]
+fn test_parse_validation_error() {
+    let error = ServiceValidationError::parse(
+        r#"{"safeTxGas": ["A valid integer is required."], "nonFieldErrors": ["Bad nonce"]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        error.messages("safeTxGas"),
+        ["A valid integer is required."]
+    );
+    assert!(error.to_string().contains("; Bad nonce"));
+    assert!(error
+        .to_string()
+        .contains("; safeTxGas: A valid integer is required."));
+    assert!(error.messages("nonce").is_empty());
+    assert_eq!(ServiceValidationError::parse(r#"{"code": 1}"#), None);
+    assert_eq!(ServiceValidationError::parse("not json"), None);
+}
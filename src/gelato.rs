@@ -0,0 +1,84 @@
+use crate::api::{friendly_execute, friendly_execute_success, make_route, CLIENT};
+use crate::encoding::{bytes_to_hex_string, checksum_for_chain};
+use crate::safe::SafeTransaction;
+use crate::transaction::Transactionable;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+const GELATO_RELAY_URL: &str = "https://relay.gelato.digital";
+
+/// Overrides [GELATO_RELAY_URL], e.g. to point the relay calls at a mock in tests
+pub const GELATO_RELAY_URL_ENV: &str = "GELATO_RELAY_URL";
+
+fn relay_url() -> String {
+    std::env::var(GELATO_RELAY_URL_ENV).unwrap_or_else(|_| GELATO_RELAY_URL.to_owned())
+}
+
+/// Identifies a call submitted to the Gelato relay
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct TaskId(pub String);
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SponsoredCallRequest {
+    chain_id: u64,
+    target: String,
+    data: String,
+    sponsor_api_key: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SponsoredCallResponse {
+    task_id: TaskId,
+}
+
+#[derive(Deserialize, Debug)]
+struct TaskStatusResponse {
+    task: TaskStatus,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatus {
+    pub task_id: TaskId,
+    /// e.g. "CheckPending", "ExecPending", "ExecSuccess", "ExecReverted" or "Cancelled"
+    pub task_state: String,
+    pub transaction_hash: Option<String>,
+    pub last_check_message: Option<String>,
+}
+
+impl<T: Transactionable> SafeTransaction<T> {
+    /// Submits `execTransaction` to the Gelato relay as a sponsored call, Gelato pays the gas
+    pub async fn relay_gelato(&self, signatures: &str, api_key: &str) -> anyhow::Result<TaskId> {
+        let request = SponsoredCallRequest {
+            chain_id: self.chain_id,
+            target: checksum_for_chain(&self.safe_address, self.chain_id),
+            data: "0x".to_owned() + &bytes_to_hex_string(self.exec_calldata(signatures)?),
+            sponsor_api_key: api_key.to_owned(),
+        };
+        debug!(
+            "relaying transaction for safe {} via gelato",
+            request.target
+        );
+
+        let response: SponsoredCallResponse = friendly_execute_success(
+            CLIENT
+                .post(make_route(
+                    &relay_url(),
+                    &["relays", "v2", "sponsored-call"],
+                ))
+                .json(&request),
+        )
+        .await?;
+        Ok(response.task_id)
+    }
+}
+
+pub async fn gelato_task_status(task_id: &TaskId) -> anyhow::Result<TaskStatus> {
+    let response: TaskStatusResponse =
+        friendly_execute(CLIENT.get(make_route(&relay_url(), &["tasks", "status", &task_id.0])))
+            .await?;
+    Ok(response.task)
+}
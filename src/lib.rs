@@ -1,7 +1,23 @@
+pub mod admin;
+pub mod allowance;
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod bundle;
+pub mod chain;
+pub mod clock;
 pub mod constants;
+pub mod decode;
+pub mod deployment;
+pub mod describe;
 pub mod encoding;
+pub mod error;
+#[cfg(feature = "gelato")]
+pub mod gelato;
+pub mod module;
+pub mod onchain;
 pub mod safe;
+pub mod signature;
 pub mod transaction;
 pub mod types;
+pub mod version;
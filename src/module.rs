@@ -0,0 +1,74 @@
+use crate::encoding::{encode_function_call, operation_to_u8};
+use crate::transaction::Transactionable;
+use ethers::abi::Token;
+use ethers::types::{Address, TransactionRequest, U256};
+use safe_client_gateway::common::models::data_decoded::Operation;
+
+/// `execTransactionFromModule(to, value, data, operation)` on a safe, sent by an enabled module
+///
+/// Modules are authorized by being enabled so no signatures are involved, send this from the module
+/// itself rather than wrapping it in a [crate::types::SafeTransaction]
+#[derive(Debug, Clone)]
+pub struct ModuleExecution {
+    pub safe: Address,
+    pub to: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+    pub operation: Operation,
+}
+
+impl ModuleExecution {
+    /// The transaction to send from `module`
+    pub fn into_request(self, module: Address) -> TransactionRequest {
+        TransactionRequest::new()
+            .from(module)
+            .to(self.safe)
+            .data(self.encode())
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        encode_function_call(
+            "execTransactionFromModule(address,uint256,bytes,uint8)",
+            &[
+                Token::Address(self.to),
+                Token::Uint(self.value),
+                Token::Bytes(self.data.clone()),
+                Token::Uint(U256::from(operation_to_u8(self.operation))),
+            ],
+        )
+    }
+}
+
+impl Transactionable for ModuleExecution {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.encode())
+    }
+
+    /// The safe, the inner target is [ModuleExecution::to]
+    fn to(&self) -> Address {
+        self.safe
+    }
+
+    /// Always zero, the safe pays `value` out of its own balance
+    fn value(&self) -> U256 {
+        U256::zero()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_module_execution() {
+    let safe = Address::repeat_byte(1);
+    let execution = ModuleExecution {
+        safe,
+        to: Address::repeat_byte(2),
+        value: U256::from(5),
+        data: vec![],
+        operation: Operation::DELEGATE,
+    };
+    assert_eq!(execution.to(), safe);
+
+    let calldata = execution.calldata().unwrap();
+    assert_eq!(calldata[..4], [0x46, 0x87, 0x21, 0xa7]);
+    assert_eq!(calldata[4 + 4 * 32 - 1], 1);
+}
@@ -0,0 +1,171 @@
+use crate::constants::SENTINEL_ADDRESS;
+use crate::safe::GnosisSafe;
+use ethers::providers::Middleware;
+use ethers::types::{Address, U256};
+use std::sync::Arc;
+
+/// Where the service's view of a safe's owners disagrees with the contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnersDiff {
+    /// Owners on chain that the service does not report
+    pub added: Vec<Address>,
+    /// Owners the service reports that are no longer on chain
+    pub removed: Vec<Address>,
+    pub service_threshold: u32,
+    pub onchain_threshold: u32,
+}
+
+impl OwnersDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.service_threshold == self.onchain_threshold
+    }
+}
+
+/// Compares the owners and threshold reported by the service with `getOwners` and `getThreshold`
+pub async fn verify_owners_onchain<M: Middleware + 'static>(
+    chain_id: u64,
+    safe_address: Address,
+    middleware: Arc<M>,
+) -> anyhow::Result<OwnersDiff> {
+    let service = crate::api::policy(chain_id, safe_address).await?;
+
+    let instance = GnosisSafe::new(safe_address, middleware);
+    let onchain_owners = instance.get_owners().call().await?;
+    let onchain_threshold = instance.get_threshold().call().await?;
+
+    Ok(OwnersDiff {
+        added: onchain_owners
+            .iter()
+            .filter(|owner| !service.is_owner(**owner))
+            .copied()
+            .collect(),
+        removed: service
+            .owners
+            .iter()
+            .filter(|owner| !onchain_owners.contains(owner))
+            .copied()
+            .collect(),
+        service_threshold: service.threshold,
+        onchain_threshold: u32::try_from(onchain_threshold).map_err(anyhow::Error::msg)?,
+    })
+}
+
+/// The nonce the service reports next to the contract's, the service can lag a few blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceDrift {
+    pub service_nonce: U256,
+    pub onchain_nonce: U256,
+}
+
+impl NonceDrift {
+    /// How many transactions the chain is ahead of the service, negative if it is behind
+    pub fn delta(&self) -> i128 {
+        self.onchain_nonce.low_u128() as i128 - self.service_nonce.low_u128() as i128
+    }
+}
+
+/// Compares the nonce reported by the service with `nonce()` on the contract
+pub async fn nonce_drift<M: Middleware + 'static>(
+    chain_id: u64,
+    safe_address: Address,
+    middleware: Arc<M>,
+) -> anyhow::Result<NonceDrift> {
+    let service_nonce = crate::api::safes(chain_id, safe_address)
+        .await?
+        .safe_config
+        .nonce;
+    let onchain_nonce = GnosisSafe::new(safe_address, middleware)
+        .nonce()
+        .call()
+        .await?;
+
+    Ok(NonceDrift {
+        service_nonce: U256::from(service_nonce),
+        onchain_nonce,
+    })
+}
+
+const MODULES_PAGE_SIZE: u64 = 16;
+
+/// The modules enabled on chain, in contract order, less stale than [crate::api::modules]
+pub async fn modules_onchain<M: Middleware + 'static>(
+    safe_address: Address,
+    middleware: Arc<M>,
+) -> anyhow::Result<Vec<Address>> {
+    let instance = GnosisSafe::new(safe_address, middleware);
+    let mut modules = vec![];
+    let mut start = *SENTINEL_ADDRESS;
+    loop {
+        let (page, next) = instance
+            .get_modules_paginated(start, U256::from(MODULES_PAGE_SIZE))
+            .call()
+            .await?;
+        match extend_modules_page(&mut modules, page, next) {
+            Some(next) => start = next,
+            None => return Ok(modules),
+        }
+    }
+}
+
+/// Appends a page of `getModulesPaginated`, returning where the next page starts if there is one
+///
+/// Before v1.4.1 `next` is the first module not yet returned rather than the last one returned, and
+/// the next page starts after it
+fn extend_modules_page(
+    modules: &mut Vec<Address>,
+    page: Vec<Address>,
+    next: Address,
+) -> Option<Address> {
+    if page.is_empty() || next == *SENTINEL_ADDRESS || next.is_zero() {
+        modules.extend(page);
+        return None;
+    }
+    let next_returned = page.last() == Some(&next);
+    modules.extend(page);
+    if !next_returned {
+        modules.push(next);
+    }
+    Some(next)
+}
+
+#[cfg(test)]
+#[test]
+fn test_modules_page_boundary() {
+    let enabled: Vec<Address> = (1..=5).map(Address::repeat_byte).collect();
+    let following = |module: Address| {
+        enabled
+            .iter()
+            .position(|enabled| *enabled == module)
+            .map_or(enabled[0], |i| {
+                enabled.get(i + 1).copied().unwrap_or(*SENTINEL_ADDRESS)
+            })
+    };
+    // getModulesPaginated with a page size of 2 as deployed before and since v1.4.1
+    let paginated = |start: Address, since_v1_4_1: bool| {
+        let mut page = vec![];
+        let mut current = following(start);
+        while current != *SENTINEL_ADDRESS && page.len() < 2 {
+            page.push(current);
+            current = following(current);
+        }
+        match page.last() {
+            Some(last) if since_v1_4_1 && current != *SENTINEL_ADDRESS => (page.clone(), *last),
+            _ => (page, current),
+        }
+    };
+
+    for since_v1_4_1 in [false, true] {
+        let mut modules = vec![];
+        let mut start = *SENTINEL_ADDRESS;
+        loop {
+            let (page, next) = paginated(start, since_v1_4_1);
+            match extend_modules_page(&mut modules, page, next) {
+                Some(next) => start = next,
+                None => break,
+            }
+        }
+        assert_eq!(modules, enabled);
+    }
+}
@@ -1,15 +1,22 @@
-use super::transaction::Transactionable;
+use super::transaction::{RawCall, Transactionable};
+use crate::admin::SafePolicy;
 use crate::bundle::Bundle;
-use crate::constants::{DOMAIN_TYPE_HASH, PAYLOAD_TYPE_HASH};
+use crate::constants::{DOMAIN_TYPE_HASH, LEGACY_DOMAIN_TYPE_HASH, PAYLOAD_TYPE_HASH};
+use crate::encoding::{
+    bytes_to_hex_string, checksum_for_chain, encode_function_call, hex_string_to_bytes,
+    operation_to_u8,
+};
+use crate::signature::{approved_hash_signature, to_safe_encoding};
+use crate::version::SafeVersion;
 use ethers::prelude::abigen;
 use ethers::prelude::builders::ContractCall;
 use ethers::providers::Middleware;
-use ethers::types::Signature;
-use ethers::types::{Address, U256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, Signature, TransactionRequest, H256, U256};
 use ethers::utils::keccak256;
 use ethers::{
     abi,
-    abi::Token,
+    abi::{AbiParser, Token},
     types::transaction::eip712::{EIP712Domain, Eip712, Eip712Error},
 };
 use itertools::Itertools;
@@ -17,6 +24,9 @@ use safe_client_gateway::common::models::data_decoded::Operation;
 use safe_client_gateway::routes::transactions::models::details::{
     DetailedExecutionInfo, TransactionDetails,
 };
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
 use tracing::info;
 
 abigen!(GnosisSafe, "abi/gnosis_safe.json",);
@@ -26,9 +36,39 @@ pub struct SignedSafePayload<T: Transactionable> {
     pub payload: SafeTransaction<T>,
     pub signature: Signature,
     pub sender: Address,
+    /// Attributes the proposal to a tool in the UI, not part of the signed payload
+    pub origin: Option<String>,
+}
+
+impl<T: Transactionable> SignedSafePayload<T> {
+    /// Shown in the UI as the source of the proposal, either plain text or a JSON string
+    pub fn origin(mut self, origin: &str) -> Self {
+        self.origin = Some(origin.to_owned());
+        self
+    }
+}
+
+/// The fee model of the outer ethereum transaction sending `execTransaction`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TxType {
+    /// A pre EIP-1559 transaction with a single gas price
+    Legacy,
+    #[default]
+    Eip1559,
 }
 
-/// defaults to a CALL operation
+/// How to send `execTransaction`, unset values are filled in by the middleware
+///
+/// For legacy transactions `max_fee` is used as the gas price and `max_priority_fee` is ignored
+#[derive(Debug, Clone, Default)]
+pub struct ExecOptions {
+    pub tx_type: TxType,
+    pub max_fee: Option<U256>,
+    pub max_priority_fee: Option<U256>,
+    pub gas_limit: Option<U256>,
+}
+
+/// defaults to the transaction's own operation, a CALL unless it says otherwise
 /// Defaults to getting the nonce from the contract
 #[derive(Debug, Clone)]
 pub struct SafeTransactionBuilder<T: Transactionable> {
@@ -42,6 +82,8 @@ pub struct SafeTransactionBuilder<T: Transactionable> {
     pub refund_receiver: Option<Address>,
     pub nonce: Option<U256>,
     pub operation: Option<Operation>,
+    pub safe_version: Option<String>,
+    pub origin: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -56,6 +98,8 @@ pub struct SafeTransaction<T: Transactionable> {
     pub refund_receiver: Address,
     pub nonce: U256,
     pub operation: Operation,
+    /// None is treated as a current (>= v1.3.0) safe
+    pub safe_version: Option<SafeVersion>,
 }
 
 pub fn attempt_extract_nonce(tx: &TransactionDetails) -> Option<u64> {
@@ -65,16 +109,82 @@ pub fn attempt_extract_nonce(tx: &TransactionDetails) -> Option<u64> {
     }
 }
 
-impl<T: Transactionable> Eip712 for SafeTransaction<T> {
+/// Estimates `call` and scales the estimate up by `multiplier`, rounding up
+async fn estimate_with_buffer<M: Middleware + 'static>(
+    middleware: &M,
+    call: &TypedTransaction,
+    multiplier: f64,
+) -> anyhow::Result<U256> {
+    if !multiplier.is_finite() || multiplier < 1.0 {
+        anyhow::bail!("gas multiplier {multiplier} must be at least 1");
+    }
+    let gas = middleware.estimate_gas(call, None).await?;
+    let basis_points = U256::from((multiplier * 10_000.0).ceil() as u64);
+    Ok((gas * basis_points + 9_999) / 10_000)
+}
+
+/// The confirmations needed to execute, as recorded when the transaction was proposed
+///
+/// This governs execution and can differ from the safe's current threshold if it changed since
+pub fn effective_threshold(tx: &TransactionDetails) -> Option<u64> {
+    match &tx.detailed_execution_info {
+        Some(DetailedExecutionInfo::Multisig(info)) => Some(info.confirmations_required),
+        _ => None,
+    }
+}
+
+/// Whether enough owners confirmed to execute, judged against [effective_threshold]
+pub fn is_executable(tx: &TransactionDetails) -> bool {
+    match &tx.detailed_execution_info {
+        Some(DetailedExecutionInfo::Multisig(info)) => {
+            info.confirmations.len() as u64 >= info.confirmations_required
+        }
+        _ => false,
+    }
+}
+
+/// The raw `SafeTx` struct with the domain it is signed in, for hashing without a builder
+///
+/// Fields are in the order of the contract's `SafeTx` type hash
+#[derive(Debug, Clone)]
+pub struct SafeTx {
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub operation: Operation,
+    pub safe_tx_gas: U256,
+    pub base_gas: U256,
+    pub gas_price: U256,
+    pub gas_token: Address,
+    pub refund_receiver: Address,
+    pub nonce: U256,
+    pub chain_id: u64,
+    /// The safe, the verifying contract of the domain
+    pub safe_address: Address,
+    /// None means a current safe, see [SafeVersion::domain_includes_chain_id]
+    pub safe_version: Option<SafeVersion>,
+}
+
+impl SafeTx {
+    fn domain_includes_chain_id(&self) -> bool {
+        self.safe_version
+            .map_or(true, |version| version.domain_includes_chain_id())
+    }
+}
+
+impl Eip712 for SafeTx {
     type Error = Eip712Error;
     fn domain_separator(&self) -> Result<[u8; 32], Self::Error> {
-        let encoded = abi::encode(&[
-            Token::FixedBytes(DOMAIN_TYPE_HASH.clone()),
-            Token::Uint(U256::from(self.chain_id)),
-            Token::Address(self.safe_address),
-        ]);
-
-        Ok(keccak256(encoded))
+        if self.domain_includes_chain_id() {
+            Ok(SafeTransaction::<RawCall>::domain_separator(
+                self.chain_id,
+                self.safe_address,
+            ))
+        } else {
+            Ok(SafeTransaction::<RawCall>::legacy_domain_separator(
+                self.safe_address,
+            ))
+        }
     }
 
     fn type_hash() -> Result<[u8; 32], Self::Error> {
@@ -88,7 +198,9 @@ impl<T: Transactionable> Eip712 for SafeTransaction<T> {
         Ok(EIP712Domain {
             name: None,
             version: None,
-            chain_id: Some(U256::from(self.chain_id)),
+            chain_id: self
+                .domain_includes_chain_id()
+                .then(|| U256::from(self.chain_id)),
             verifying_contract: Some(self.safe_address),
             salt: None,
         })
@@ -97,20 +209,10 @@ impl<T: Transactionable> Eip712 for SafeTransaction<T> {
     fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
         Ok(keccak256(abi::encode(&[
             Token::FixedBytes(PAYLOAD_TYPE_HASH.clone()),
-            Token::Address(self.tx.to()),
-            Token::Uint(self.tx.value()),
-            Token::FixedBytes(
-                keccak256(
-                    self.tx
-                        .calldata()
-                        .map_err(|_| Eip712Error::FailedToEncodeStruct)?,
-                )
-                .to_vec(),
-            ), // see EIP-712, bytes are hashed
-            Token::Uint(match self.operation {
-                Operation::CALL => U256::from(0),
-                Operation::DELEGATE => U256::from(1),
-            }),
+            Token::Address(self.to),
+            Token::Uint(self.value),
+            Token::FixedBytes(keccak256(&self.data).to_vec()), // see EIP-712, bytes are hashed
+            Token::Uint(U256::from(operation_to_u8(self.operation))),
             Token::Uint(self.safe_tx_gas),
             Token::Uint(self.base_gas),
             Token::Uint(self.gas_price),
@@ -121,12 +223,33 @@ impl<T: Transactionable> Eip712 for SafeTransaction<T> {
     }
 }
 
+/// Hashes through [SafeTx]
+impl<T: Transactionable> Eip712 for SafeTransaction<T> {
+    type Error = Eip712Error;
+    fn domain_separator(&self) -> Result<[u8; 32], Self::Error> {
+        self.safe_tx()?.domain_separator()
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        SafeTx::type_hash()
+    }
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        self.safe_tx()?.domain()
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        self.safe_tx()?.struct_hash()
+    }
+}
+
 impl<T: Transactionable> SafeTransactionBuilder<T> {
     pub async fn build(self) -> anyhow::Result<SafeTransaction<T>> {
         let nonce = match self.nonce {
             Some(nonce) => nonce,
             None => self.next_nonce().await?,
         };
+        let operation = self.operation.unwrap_or_else(|| self.tx.operation());
         Ok(SafeTransaction {
             tx: self.tx,
             chain_id: self.chain_id,
@@ -137,10 +260,22 @@ impl<T: Transactionable> SafeTransactionBuilder<T> {
             gas_token: self.gas_token.unwrap_or(Address::zero()),
             refund_receiver: self.refund_receiver.unwrap_or(Address::zero()),
             nonce: nonce,
-            operation: self.operation.unwrap_or(Operation::CALL),
+            operation,
+            safe_version: self.safe_version.as_deref().map(str::parse).transpose()?,
         })
     }
 
+    /// Builds and signs the transaction, carrying the builder's origin into the proposal
+    pub async fn build_and_sign<S>(self, signer: &S) -> anyhow::Result<SignedSafePayload<T>>
+    where
+        S: ethers::signers::Signer,
+        S::Error: 'static,
+    {
+        let origin = self.origin.clone();
+        let signed = self.build().await?.sign_safe_tx(signer).await?;
+        Ok(SignedSafePayload { origin, ..signed })
+    }
+
     pub fn new(tx: T, chain_id: u64, safe_address: Address) -> Self {
         Self {
             tx,
@@ -153,6 +288,8 @@ impl<T: Transactionable> SafeTransactionBuilder<T> {
             refund_receiver: None,
             nonce: None,
             operation: None,
+            safe_version: None,
+            origin: None,
         }
     }
 
@@ -165,6 +302,29 @@ impl<T: Transactionable> SafeTransactionBuilder<T> {
         SafeTransactionBuilder::new(bundle, chain_id, safe_address).operation(Operation::DELEGATE)
     }
 
+    /// Estimates the gas of the inner call made from the safe, scaled by `multiplier` (e.g. 1.25)
+    ///
+    /// A suggestion for [Self::safe_tx_gas] when the service's estimate is too low, delegate calls
+    /// run in the safe's context and can't be estimated this way
+    pub async fn estimate_inner_gas<M: Middleware + 'static>(
+        &self,
+        middleware: Arc<M>,
+        multiplier: f64,
+    ) -> anyhow::Result<U256> {
+        let operation = self.operation.unwrap_or_else(|| self.tx.operation());
+        if matches!(operation, Operation::DELEGATE) {
+            anyhow::bail!("can't estimate the gas of a delegate call from the safe");
+        }
+
+        let call: TypedTransaction = TransactionRequest::new()
+            .from(self.safe_address)
+            .to(self.tx.to())
+            .value(self.tx.value())
+            .data(self.tx.calldata()?)
+            .into();
+        estimate_with_buffer(&middleware, &call, multiplier).await
+    }
+
     pub async fn next_nonce(&self) -> anyhow::Result<U256> {
         Ok(U256::from(
             crate::api::safes(self.chain_id, self.safe_address)
@@ -208,9 +368,227 @@ impl<T: Transactionable> SafeTransactionBuilder<T> {
         self.operation = Some(operation);
         self
     }
+
+    /// The version of the safe contract, see `SafeInfoEx::version`
+    ///
+    /// Safes before v1.3.0 sign over a domain without the chainId
+    pub fn safe_version(mut self, version: &str) -> Self {
+        self.safe_version = Some(version.to_owned());
+        self
+    }
+
+    /// Shown in the UI as the source of the proposal, either plain text or a JSON string
+    pub fn origin(mut self, origin: &str) -> Self {
+        self.origin = Some(origin.to_owned());
+        self
+    }
+
+    /// Tags the proposal the way the official UI does, as an origin of `{"name": .., "url": ..}`
+    pub fn label(self, name: &str, url: Option<&str>) -> Self {
+        let mut origin = json!({ "name": name });
+        if let Some(url) = url {
+            origin["url"] = json!(url);
+        }
+        self.origin(&origin.to_string())
+    }
+
+    /// Refunds gas in `token`, converting `native_gas_price` (in wei) with a quote of how much
+    /// native currency one whole token is worth
+    ///
+    /// The price is rounded up so the refund never falls short of the gas spent. Takes the native
+    /// gas price rather than a gas unit count, as only the price per gas is set on the transaction
+    ///
+    /// Errors if the price overflows a uint256 in the token's smallest unit
+    pub fn refund_in_token(
+        mut self,
+        token: Address,
+        token_decimals: u8,
+        native_per_token: f64,
+        native_gas_price: U256,
+    ) -> anyhow::Result<Self> {
+        if !native_per_token.is_finite() || native_per_token <= 0.0 {
+            anyhow::bail!("invalid quote of {native_per_token} native per token");
+        }
+        // the quote in wei per whole token, keeping the division below in integers
+        let wei_per_token = U256::from((native_per_token * 1e18).round() as u128);
+        if wei_per_token.is_zero() {
+            anyhow::bail!("quote of {native_per_token} native per token is below 1 wei");
+        }
+        let scaled = U256::from(10)
+            .checked_pow(U256::from(token_decimals))
+            .and_then(|unit| native_gas_price.checked_mul(unit))
+            .and_then(|scaled| scaled.checked_add(wei_per_token - 1))
+            .ok_or_else(|| anyhow::anyhow!("gas price overflows with {token_decimals} decimals"))?;
+        self.gas_price = Some(scaled / wei_per_token);
+        self.gas_token = Some(token);
+        Ok(self)
+    }
+}
+
+impl SafeTransactionBuilder<RawCall> {
+    /// A call to any contract with already encoded calldata
+    pub fn raw_call(
+        chain_id: u64,
+        safe_address: Address,
+        to: Address,
+        value: U256,
+        data: Vec<u8>,
+    ) -> Self {
+        SafeTransactionBuilder::new(RawCall { to, value, data }, chain_id, safe_address)
+    }
+
+    /// Encodes a call from a human readable signature, e.g. `"vote(uint256 id, bool support)"`
+    pub fn call_abi(
+        chain_id: u64,
+        safe_address: Address,
+        to: Address,
+        signature: &str,
+        tokens: Vec<Token>,
+    ) -> anyhow::Result<Self> {
+        let function = AbiParser::default().parse_function(signature)?;
+        let param_types = function
+            .inputs
+            .iter()
+            .map(|param| param.kind.clone())
+            .collect::<Vec<_>>();
+        if !Token::types_check(&tokens, &param_types) {
+            anyhow::bail!(
+                "arguments {tokens:?} don't match the parameters {param_types:?} of {signature}"
+            );
+        }
+        Ok(Self::raw_call(
+            chain_id,
+            safe_address,
+            to,
+            U256::zero(),
+            function.encode_input(&tokens)?,
+        ))
+    }
+
+    /// Spends from `delegate`'s AllowanceModule limit on `token`, as the safe is the sender the
+    /// module needs the delegate's signature over the transfer hash unless the safe is the delegate
+    #[allow(clippy::too_many_arguments)]
+    pub fn allowance_transfer(
+        chain_id: u64,
+        safe_address: Address,
+        module: Address,
+        token: Address,
+        to: Address,
+        amount: U256,
+        delegate: Address,
+        delegate_signature: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let signature = match delegate_signature {
+            Some(signature) => hex_string_to_bytes(&signature)?,
+            None => vec![],
+        };
+        Ok(Self::raw_call(
+            chain_id,
+            safe_address,
+            module,
+            U256::zero(),
+            crate::allowance::transfer_calldata(
+                safe_address,
+                token,
+                to,
+                amount,
+                delegate,
+                signature,
+            )?,
+        ))
+    }
+
+    /// [Self::allowance_transfer] after checking `amount` against the remaining allowance on chain
+    #[allow(clippy::too_many_arguments)]
+    pub async fn allowance_transfer_checked<M: Middleware + 'static>(
+        chain_id: u64,
+        safe_address: Address,
+        module: Address,
+        token: Address,
+        to: Address,
+        amount: U256,
+        delegate: Address,
+        delegate_signature: Option<String>,
+        middleware: Arc<M>,
+    ) -> anyhow::Result<Self> {
+        let allowance = crate::allowance::allowance(
+            chain_id,
+            safe_address,
+            delegate,
+            token,
+            Some(module),
+            middleware,
+        )
+        .await?;
+        if amount > allowance.remaining() {
+            anyhow::bail!(
+                "transfer of {amount} exceeds the remaining allowance of {}",
+                allowance.remaining()
+            );
+        }
+        Self::allowance_transfer(
+            chain_id,
+            safe_address,
+            module,
+            token,
+            to,
+            amount,
+            delegate,
+            delegate_signature,
+        )
+    }
 }
 
 impl<T: Transactionable> SafeTransaction<T> {
+    /// The EIP-712 domain separator of a safe, useful for validating signatures made elsewhere
+    ///
+    /// keccak256(EIP712Domain(chainId, verifyingContract=safe))
+    pub fn domain_separator(chain_id: u64, safe: Address) -> [u8; 32] {
+        keccak256(abi::encode(&[
+            Token::FixedBytes(DOMAIN_TYPE_HASH.clone()),
+            Token::Uint(U256::from(chain_id)),
+            Token::Address(safe),
+        ]))
+    }
+
+    /// The domain separator used by safes before v1.3.0
+    ///
+    /// keccak256(EIP712Domain(verifyingContract=safe))
+    pub fn legacy_domain_separator(safe: Address) -> [u8; 32] {
+        keccak256(abi::encode(&[
+            Token::FixedBytes(LEGACY_DOMAIN_TYPE_HASH.clone()),
+            Token::Address(safe),
+        ]))
+    }
+
+    fn domain_includes_chain_id(&self) -> bool {
+        self.safe_version
+            .map_or(true, |version| version.domain_includes_chain_id())
+    }
+
+    /// The `SafeTx` struct signed for this transaction
+    pub fn safe_tx(&self) -> Result<SafeTx, Eip712Error> {
+        Ok(SafeTx {
+            to: self.tx.to(),
+            value: self.tx.value(),
+            data: self
+                .tx
+                .calldata()
+                .map_err(|_| Eip712Error::FailedToEncodeStruct)?
+                .into(),
+            operation: self.operation,
+            safe_tx_gas: self.safe_tx_gas,
+            base_gas: self.base_gas,
+            gas_price: self.gas_price,
+            gas_token: self.gas_token,
+            refund_receiver: self.refund_receiver,
+            nonce: self.nonce,
+            chain_id: self.chain_id,
+            safe_address: self.safe_address,
+            safe_version: self.safe_version,
+        })
+    }
+
     pub async fn new(
         tx: T,
         chain_id: u64,
@@ -234,21 +612,238 @@ impl<T: Transactionable> SafeTransaction<T> {
             refund_receiver: refund_receiver,
             nonce,
             operation,
+            safe_version: None,
         })
     }
 
-    pub async fn sign_safe_tx<S: 'static + ethers::signers::Signer>(
-        self,
-        signer: &S,
-    ) -> anyhow::Result<SignedSafePayload<T>> {
+    /// Works with any ethers signer, including ones that borrow a client such as `AwsSigner<'a>`
+    pub async fn sign_safe_tx<S>(self, signer: &S) -> anyhow::Result<SignedSafePayload<T>>
+    where
+        S: ethers::signers::Signer,
+        S::Error: 'static,
+    {
         info!("Signing Safe Transaction");
         Ok(SignedSafePayload {
             signature: signer.sign_typed_data(&self).await?,
             payload: self,
             sender: signer.address(),
+            origin: None,
         })
     }
 
+    /// Signs with every signer, returning the payload signed by the first one for proposing and all
+    /// the signatures sorted by owner and joined, ready for execution
+    ///
+    /// Errors if a signer is given twice, the contract rejects repeated owners. With a `policy`,
+    /// also errors if any signer is not an owner
+    pub async fn sign_with_all<S>(
+        self,
+        signers: &[S],
+        policy: Option<&SafePolicy>,
+    ) -> anyhow::Result<(SignedSafePayload<T>, String)>
+    where
+        S: ethers::signers::Signer,
+        S::Error: 'static,
+    {
+        if signers.is_empty() {
+            anyhow::bail!("no signers given");
+        }
+        let mut addresses = HashSet::new();
+        if let Some(signer) = signers
+            .iter()
+            .find(|signer| !addresses.insert(signer.address()))
+        {
+            anyhow::bail!("{:?} is given more than once", signer.address());
+        }
+        if let Some(signer) = policy.and_then(|policy| {
+            signers
+                .iter()
+                .find(|signer| !policy.is_owner(signer.address()))
+        }) {
+            anyhow::bail!("{:?} is not an owner of the safe", signer.address());
+        }
+
+        let mut signatures = vec![];
+        for signer in signers {
+            signatures.push((signer.address(), signer.sign_typed_data(&self).await?));
+        }
+        let joined = sort_and_join_sigs(
+            &signatures
+                .iter()
+                .map(|(owner, signature)| (*owner, to_safe_encoding(signature, false)))
+                .collect(),
+        );
+
+        let (sender, signature) = signatures.swap_remove(0);
+        Ok((
+            SignedSafePayload {
+                payload: self,
+                signature,
+                sender,
+                origin: None,
+            },
+            joined,
+        ))
+    }
+
+    /// The full EIP-712 typed data of this transaction, for signers that take the raw JSON
+    ///
+    /// Hashes to exactly what [Eip712::encode_eip712] produces, including the legacy domain
+    pub fn to_eip712_json(&self) -> anyhow::Result<serde_json::Value> {
+        let mut domain_type = vec![];
+        let mut domain = serde_json::Map::new();
+        if self.domain_includes_chain_id() {
+            domain_type.push(json!({ "name": "chainId", "type": "uint256" }));
+            domain.insert("chainId".to_owned(), json!(self.chain_id));
+        }
+        domain_type.push(json!({ "name": "verifyingContract", "type": "address" }));
+        domain.insert(
+            "verifyingContract".to_owned(),
+            json!(checksum_for_chain(&self.safe_address, self.chain_id)),
+        );
+
+        Ok(json!({
+            "types": {
+                "EIP712Domain": domain_type,
+                "SafeTx": [
+                    { "name": "to", "type": "address" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "data", "type": "bytes" },
+                    { "name": "operation", "type": "uint8" },
+                    { "name": "safeTxGas", "type": "uint256" },
+                    { "name": "baseGas", "type": "uint256" },
+                    { "name": "gasPrice", "type": "uint256" },
+                    { "name": "gasToken", "type": "address" },
+                    { "name": "refundReceiver", "type": "address" },
+                    { "name": "nonce", "type": "uint256" },
+                ],
+            },
+            "primaryType": "SafeTx",
+            "domain": domain,
+            "message": {
+                "to": checksum_for_chain(&self.tx.to(), self.chain_id),
+                "value": self.tx.value().to_string(),
+                "data": "0x".to_owned() + &bytes_to_hex_string(self.tx.calldata()?),
+                "operation": operation_to_u8(self.operation),
+                "safeTxGas": self.safe_tx_gas.to_string(),
+                "baseGas": self.base_gas.to_string(),
+                "gasPrice": self.gas_price.to_string(),
+                "gasToken": checksum_for_chain(&self.gas_token, self.chain_id),
+                "refundReceiver": checksum_for_chain(&self.refund_receiver, self.chain_id),
+                "nonce": self.nonce.to_string(),
+            },
+        }))
+    }
+
+    /// The `execTransaction` calldata for this transaction, e.g. to hand to a relayer
+    ///
+    /// Like [Self::contract_call] this requires the signatures encoded the way the safe expects
+    pub fn exec_calldata(&self, signatures: &str) -> anyhow::Result<Bytes> {
+        Ok(encode_function_call(
+            "execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)",
+            &[
+                Token::Address(self.tx.to()),
+                Token::Uint(self.tx.value()),
+                Token::Bytes(self.tx.calldata()?),
+                Token::Uint(U256::from(operation_to_u8(self.operation))),
+                Token::Uint(self.safe_tx_gas),
+                Token::Uint(self.base_gas),
+                Token::Uint(self.gas_price),
+                Token::Address(self.gas_token),
+                Token::Address(self.refund_receiver),
+                Token::Bytes(hex_string_to_bytes(signatures)?),
+            ],
+        )
+        .into())
+    }
+
+    /// Estimates the gas limit of the whole `execTransaction` sent by `from`, scaled by `multiplier`
+    ///
+    /// Each signature is checked on chain so the estimate grows with their count, estimate with the
+    /// signatures that will actually be sent
+    pub async fn estimate_exec_gas<M: Middleware + 'static>(
+        &self,
+        signatures: &str,
+        from: Address,
+        middleware: Arc<M>,
+        multiplier: f64,
+    ) -> anyhow::Result<U256> {
+        let call: TypedTransaction = TransactionRequest::new()
+            .from(from)
+            .to(self.safe_address)
+            .data(self.exec_calldata(signatures)?)
+            .into();
+        estimate_with_buffer(&middleware, &call, multiplier).await
+    }
+
+    /// Sends `execTransaction` with the given fee model and returns the ethereum transaction hash
+    pub async fn execute<M: Middleware + 'static>(
+        self,
+        signatures: String,
+        client: &std::sync::Arc<M>,
+        options: ExecOptions,
+    ) -> anyhow::Result<H256> {
+        let mut call = self.contract_call(signatures, client)?;
+        if let Some(gas_limit) = options.gas_limit {
+            call = call.gas(gas_limit);
+        }
+        match options.tx_type {
+            TxType::Legacy => {
+                call = call.legacy();
+                if let Some(max_fee) = options.max_fee {
+                    call = call.gas_price(max_fee);
+                }
+            }
+            TxType::Eip1559 => {
+                if let TypedTransaction::Eip1559(ref mut inner) = call.tx {
+                    inner.max_fee_per_gas = options.max_fee.or(inner.max_fee_per_gas);
+                    inner.max_priority_fee_per_gas =
+                        options.max_priority_fee.or(inner.max_priority_fee_per_gas);
+                }
+            }
+        }
+
+        let pending = call.send().await?;
+        info!("sent execTransaction {:?}", *pending);
+        Ok(*pending)
+    }
+
+    /// Signs with the sole owner of a 1-of-1 safe and executes straight away, skipping the service
+    ///
+    /// Errors unless the on-chain threshold is 1 and `signer` is the only owner
+    pub async fn execute_single<M, S>(
+        self,
+        signer: &S,
+        client: &Arc<M>,
+        options: ExecOptions,
+    ) -> anyhow::Result<H256>
+    where
+        M: Middleware + 'static,
+        S: ethers::signers::Signer,
+        S::Error: 'static,
+    {
+        let instance = GnosisSafe::new(self.safe_address, client.clone());
+        let threshold = instance.get_threshold().call().await?;
+        if threshold != U256::one() {
+            anyhow::bail!(
+                "safe {:?} has a threshold of {threshold}, not 1",
+                self.safe_address
+            );
+        }
+        let owners = instance.get_owners().call().await?;
+        if owners != [signer.address()] {
+            anyhow::bail!(
+                "{:?} is not the sole owner of safe {:?}",
+                signer.address(),
+                self.safe_address
+            );
+        }
+
+        let signed = self.sign_safe_tx(signer).await?;
+        let signature = to_safe_encoding(&signed.signature, false);
+        signed.payload.execute(signature, client, options).await
+    }
+
     /// See [sort_and_join_sigs] for more information about creating the signatures for the conract
     /// 
     /// This functions requires the signature being encoded in the way that the safe expects
@@ -275,7 +870,7 @@ impl<T: Transactionable> SafeTransaction<T> {
             tx.to(),
             tx.value(),
             tx.calldata()?.into(),
-            operation as u8,
+            operation_to_u8(operation),
             safe_tx_gas,
             base_gas,
             gas_price,
@@ -297,10 +892,28 @@ pub fn sort_and_join_sigs(sigs: &Vec<(Address, String)>) -> String {
         .join("")
 }
 
+/// Joins off-chain signatures with the approved-hash markers of owners that approved on chain (or
+/// will send `execTransaction` themselves), all sorted by owner as the contract requires
+pub fn build_execution_signatures(
+    offchain: Vec<(Address, String)>,
+    onchain_approvers: Vec<Address>,
+) -> String {
+    sort_and_join_sigs(
+        &offchain
+            .into_iter()
+            .chain(
+                onchain_approvers
+                    .into_iter()
+                    .map(|owner| (owner, approved_hash_signature(owner))),
+            )
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 #[test]
 fn test_hashing() {
-    use crate::encoding::bytes_to_hex_string;
+    use ethers::types::transaction::eip712::TypedData;
     use ethers::types::{Address, H256, U256};
 
     #[derive(Clone)]
@@ -347,6 +960,7 @@ fn test_hashing() {
             .unwrap(),
         nonce: U256::zero(),
         operation: safe_client_gateway::common::models::data_decoded::Operation::CALL,
+        safe_version: None,
     };
 
     let hash = payload.encode_eip712().unwrap();
@@ -359,6 +973,11 @@ fn test_hashing() {
         "9e77d02315090a7bc2b29a1707fd72188fa1bd7347c05a3a9a02981888cf847d"
     );
 
+    assert_eq!(
+        SafeTransaction::<Test>::domain_separator(1, payload.safe_address),
+        domain_hash
+    );
+
     assert_eq!(
         bytes_to_hex_string(type_hash),
         "bb8310d486368db6bd6f849402fdd73ad53d316b5a4b2644ad6efe0f941286d8"
@@ -368,4 +987,180 @@ fn test_hashing() {
         bytes_to_hex_string(hash),
         "0f7b372b07f04519dfa3c6e54766a16719474099fe10705fd5cd5567403134cd"
     );
+
+    let typed_data: TypedData = serde_json::from_value(payload.to_eip712_json().unwrap()).unwrap();
+    assert_eq!(typed_data.encode_eip712().unwrap(), hash);
+    assert_eq!(payload.safe_tx().unwrap().encode_eip712().unwrap(), hash);
+
+    let v1_3_0 = SafeTransaction {
+        safe_version: Some("1.3.0".parse().unwrap()),
+        ..payload.clone()
+    };
+
+    assert_eq!(
+        bytes_to_hex_string(v1_3_0.encode_eip712().unwrap()),
+        "0f7b372b07f04519dfa3c6e54766a16719474099fe10705fd5cd5567403134cd"
+    );
+
+    let v1_1_1 = SafeTransaction {
+        safe_version: Some("1.1.1".parse().unwrap()),
+        ..payload
+    };
+
+    assert_eq!(
+        bytes_to_hex_string(v1_1_1.domain_separator().unwrap()),
+        "5f07c8c9c2bb5ab0aa2c947f6cc5676416dab406cba4a7d6a18b538dd10b3bf5"
+    );
+
+    assert_eq!(
+        bytes_to_hex_string(v1_1_1.encode_eip712().unwrap()),
+        "63c06d70ee09892075db443e0ec8f1683b37d72d59b95e79a5fbdca02b2c6465"
+    );
+
+    let typed_data: TypedData = serde_json::from_value(v1_1_1.to_eip712_json().unwrap()).unwrap();
+    assert_eq!(
+        typed_data.encode_eip712().unwrap(),
+        v1_1_1.encode_eip712().unwrap()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_call_abi() {
+    let to = Address::repeat_byte(0x11);
+    let builder = SafeTransactionBuilder::call_abi(
+        1,
+        Address::repeat_byte(0x22),
+        to,
+        "vote(uint256 id, bool support)",
+        vec![Token::Uint(U256::from(7)), Token::Bool(true)],
+    )
+    .unwrap();
+    assert_eq!(builder.tx.to, to);
+    assert_eq!(
+        builder.tx.data,
+        encode_function_call(
+            "vote(uint256,bool)",
+            &[Token::Uint(U256::from(7)), Token::Bool(true)]
+        )
+    );
+
+    assert!(SafeTransactionBuilder::call_abi(
+        1,
+        Address::repeat_byte(0x22),
+        to,
+        "vote(uint256 id, bool support)",
+        vec![Token::Bool(true)],
+    )
+    .is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_refund_in_token() {
+    let usdc = Address::repeat_byte(0xaa);
+    let builder = || {
+        SafeTransactionBuilder::raw_call(1, Address::zero(), Address::zero(), U256::zero(), vec![])
+    };
+    // 1 USDC = 0.0005 ETH, i.e. 2000 USDC per ETH
+    let refund = |gas_price: U256| builder().refund_in_token(usdc, 6, 0.0005, gas_price);
+
+    // 30 gwei = 0.00000003 ETH = 0.00006 USDC per gas
+    let thirty_gwei = refund(U256::from(30_000_000_000u64)).unwrap();
+    assert_eq!(thirty_gwei.gas_token, Some(usdc));
+    assert_eq!(thirty_gwei.gas_price, Some(U256::from(60)));
+
+    // fractions of the smallest unit round up
+    assert_eq!(refund(U256::one()).unwrap().gas_price, Some(U256::one()));
+
+    assert!(builder()
+        .refund_in_token(usdc, 6, 0.0, U256::one())
+        .is_err());
+    assert!(builder()
+        .refund_in_token(usdc, 78, 0.0005, U256::one())
+        .is_err());
+    assert!(builder()
+        .refund_in_token(usdc, 18, 0.0005, U256::MAX / 2)
+        .is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_sign_with_all() {
+    use ethers::signers::{LocalWallet, Signer};
+
+    let wallet = |key: &str| key.parse::<LocalWallet>().unwrap();
+    // 0xf39F.. and 0x7099.., so the second sorts first
+    let first = wallet("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80");
+    let second = wallet("59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d");
+    let payload = SafeTransaction {
+        tx: RawCall {
+            to: Address::repeat_byte(1),
+            value: U256::zero(),
+            data: vec![],
+        },
+        safe_address: Address::repeat_byte(0x5a),
+        chain_id: 1,
+        safe_tx_gas: U256::zero(),
+        base_gas: U256::zero(),
+        gas_price: U256::zero(),
+        gas_token: Address::zero(),
+        refund_receiver: Address::zero(),
+        nonce: U256::zero(),
+        operation: Operation::CALL,
+        safe_version: None,
+    };
+    let sign = |wallet: &LocalWallet| {
+        to_safe_encoding(
+            &futures::executor::block_on(wallet.sign_typed_data(&payload)).unwrap(),
+            false,
+        )
+    };
+    let policy = SafePolicy {
+        owners: vec![first.address(), second.address()],
+        threshold: 2,
+    };
+
+    let (signed, joined) = futures::executor::block_on(
+        payload
+            .clone()
+            .sign_with_all(&[first.clone(), second.clone()], Some(&policy)),
+    )
+    .unwrap();
+    assert_eq!(signed.sender, first.address());
+    assert_eq!(joined, sign(&second) + &sign(&first));
+
+    let twice =
+        futures::executor::block_on(payload.clone().sign_with_all(&[first.clone(), first], None));
+    assert!(twice.is_err());
+
+    let outsider = wallet("5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a");
+    let not_owner =
+        futures::executor::block_on(payload.sign_with_all(&[second, outsider], Some(&policy)));
+    assert!(not_owner.is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_build_execution_signatures() {
+    let owner = |byte: u64| Address::from_low_u64_be(byte);
+    let offchain_sig = |fill: &str| fill.repeat(130);
+
+    let signatures = build_execution_signatures(
+        vec![
+            (owner(3), format!("0x{}", offchain_sig("c"))),
+            (owner(1), offchain_sig("a")),
+        ],
+        vec![owner(2)],
+    );
+
+    assert_eq!(
+        signatures,
+        [
+            offchain_sig("a"),
+            format!("{:064x}{:064x}01", 2, 0),
+            offchain_sig("c"),
+        ]
+        .concat()
+    );
 }
@@ -0,0 +1,232 @@
+use crate::constants::SAFE_MESSAGE_TYPE_HASH;
+use crate::encoding::{bytes_to_hex_string, hex_string_to_bytes};
+use crate::error::SafeError;
+use crate::transaction::RawCall;
+use crate::types::SafeTransaction;
+use ethers::abi::{self, Token};
+use ethers::types::{Address, RecoveryMessage, Signature, H256, U256};
+use ethers::utils::keccak256;
+use safe_client_gateway::routes::transactions::models::details::MultisigConfirmation;
+use std::collections::HashSet;
+
+/// Encodes a signature as the safe expects it, `r ++ s ++ v` as hex
+///
+/// EIP-712 signatures use v in {27, 28}. Signatures over the `eth_sign` prefixed hash (as produced
+/// by signing the safe tx hash as a message, e.g. on a Ledger) are marked with v + 4, in {31, 32}
+pub fn to_safe_encoding(signature: &Signature, eth_sign: bool) -> String {
+    // normalize recovery ids and already adjusted values back to 27/28
+    let v = match signature.v {
+        0 | 1 => signature.v + 27,
+        31 | 32 => signature.v - 4,
+        v => v,
+    };
+
+    bytes_to_hex_string(
+        Signature {
+            r: signature.r,
+            s: signature.s,
+            v: if eth_sign { v + 4 } else { v },
+        }
+        .to_vec(),
+    )
+}
+
+/// The signature of an owner that approved the hash on chain with `approveHash`, or is the sender of
+/// `execTransaction`: r is the owner, s is unused and v is 1
+pub fn approved_hash_signature(owner: Address) -> String {
+    bytes_to_hex_string([H256::from(owner).as_bytes(), H256::zero().as_bytes(), &[1]].concat())
+}
+
+/// The hash owners sign for an off-chain message, as computed by the compatibility fallback handler
+pub fn safe_message_hash(chain_id: u64, safe: Address, message: &[u8]) -> H256 {
+    let struct_hash = keccak256(abi::encode(&[
+        Token::FixedBytes(SAFE_MESSAGE_TYPE_HASH.clone()),
+        Token::FixedBytes(keccak256(message).to_vec()),
+    ]));
+    H256(keccak256(
+        [
+            &[0x19, 0x01],
+            &SafeTransaction::<RawCall>::domain_separator(chain_id, safe)[..],
+            &struct_hash[..],
+        ]
+        .concat(),
+    ))
+}
+
+/// Recovers the signer of one 65 byte signature in the safe's encoding over `hash`
+///
+/// Contract (v = 0) and approved hash (v = 1) signatures only name their owner, so they fail with
+/// [SafeError::NonEcdsaSignature] instead
+pub fn recover_signer(hash: H256, packed: &[u8]) -> anyhow::Result<Address> {
+    if packed.len() != 65 {
+        anyhow::bail!("signature is {} bytes, not 65", packed.len());
+    }
+    let v = packed[64];
+    let (v, message) = match v {
+        0 | 1 => {
+            return Err(SafeError::NonEcdsaSignature(Address::from_slice(&packed[12..32])).into())
+        }
+        27 | 28 => (v, RecoveryMessage::Hash(hash)),
+        31 | 32 => (v - 4, RecoveryMessage::Data(hash.as_bytes().to_vec())),
+        _ => anyhow::bail!("unsupported signature type with v = {v}"),
+    };
+    let signature = Signature {
+        r: U256::from_big_endian(&packed[..32]),
+        s: U256::from_big_endian(&packed[32..64]),
+        v: v as u64,
+    };
+    Ok(signature.recover(message)?)
+}
+
+/// Recovers who actually signed a confirmation from the service, to check it against `signer`
+pub fn recover_confirmation_signer(
+    confirmation: &MultisigConfirmation,
+    safe_tx_hash: H256,
+) -> anyhow::Result<Address> {
+    match &confirmation.signature {
+        Some(signature) => recover_signer(safe_tx_hash, &hex_string_to_bytes(signature)?),
+        None => anyhow::bail!(
+            "confirmation of {} has no signature",
+            confirmation.signer.value
+        ),
+    }
+}
+
+/// Whether the packed `signatures` over a safe message recover to at least `threshold` distinct owners
+///
+/// Both EIP-712 (v in {27, 28}) and eth_sign (v in {31, 32}) signatures are recovered, contract and
+/// approved hash signatures can't be checked off-chain and are rejected
+///
+/// A threshold of 0 is an error, no safe accepts it
+pub fn verify_safe_message(
+    safe: Address,
+    chain_id: u64,
+    message: &[u8],
+    signatures: &str,
+    owners: &[Address],
+    threshold: u32,
+) -> anyhow::Result<bool> {
+    if threshold == 0 {
+        anyhow::bail!("threshold must be at least 1");
+    }
+    let hash = safe_message_hash(chain_id, safe, message);
+    let signatures = hex_string_to_bytes(signatures)?;
+    if signatures.len() % 65 != 0 {
+        anyhow::bail!("signatures are not a multiple of 65 bytes");
+    }
+
+    let mut signers = HashSet::new();
+    for packed in signatures.chunks(65) {
+        let signer = recover_signer(hash, packed)?;
+        if owners.contains(&signer) {
+            signers.insert(signer);
+        }
+    }
+    Ok(signers.len() >= threshold as usize)
+}
+
+#[cfg(test)]
+#[test]
+fn test_safe_encoding() {
+    let signature = |v| Signature {
+        r: U256::from(1),
+        s: U256::from(2),
+        v,
+    };
+    let r_and_s = format!("{:064x}{:064x}", 1, 2);
+
+    assert_eq!(
+        to_safe_encoding(&signature(27), false),
+        format!("{r_and_s}1b")
+    );
+    assert_eq!(
+        to_safe_encoding(&signature(28), false),
+        format!("{r_and_s}1c")
+    );
+    assert_eq!(
+        to_safe_encoding(&signature(0), false),
+        format!("{r_and_s}1b")
+    );
+    assert_eq!(
+        to_safe_encoding(&signature(27), true),
+        format!("{r_and_s}1f")
+    );
+    assert_eq!(
+        to_safe_encoding(&signature(1), true),
+        format!("{r_and_s}20")
+    );
+    assert_eq!(
+        to_safe_encoding(&signature(32), true),
+        format!("{r_and_s}20")
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_approved_hash_signature() {
+    assert_eq!(
+        approved_hash_signature(Address::from_low_u64_be(0xab)),
+        format!("{:064x}{:064x}01", 0xab, 0)
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_recover_signer() {
+    use ethers::signers::{LocalWallet, Signer};
+
+    let wallet: LocalWallet = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        .parse()
+        .unwrap();
+    let hash = H256::repeat_byte(7);
+    let signature = futures::executor::block_on(wallet.sign_message(hash)).unwrap();
+
+    let eth_sign = hex_string_to_bytes(&to_safe_encoding(&signature, true)).unwrap();
+    assert_eq!(recover_signer(hash, &eth_sign).unwrap(), wallet.address());
+    // the same signature is a plain one over the prefixed hash
+    let prefixed = ethers::utils::hash_message(hash);
+    let plain = hex_string_to_bytes(&to_safe_encoding(&signature, false)).unwrap();
+    assert_eq!(recover_signer(prefixed, &plain).unwrap(), wallet.address());
+
+    let approved = hex_string_to_bytes(&approved_hash_signature(wallet.address())).unwrap();
+    let error = recover_signer(hash, &approved).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SafeError>(),
+        Some(SafeError::NonEcdsaSignature(owner)) if *owner == wallet.address()
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_verify_safe_message() {
+    use ethers::signers::{LocalWallet, Signer};
+
+    let wallet = |key: &str| key.parse::<LocalWallet>().unwrap();
+    let first = wallet("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80");
+    let second = wallet("59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d");
+    let outsider = wallet("5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a");
+    let owners = [first.address(), second.address()];
+
+    let safe = Address::repeat_byte(0x5a);
+    let message = b"hello safe";
+    let hash = safe_message_hash(1, safe, message);
+    let sign = |wallet: &LocalWallet| {
+        to_safe_encoding(
+            &futures::executor::block_on(wallet.sign_message(hash)).unwrap(),
+            true,
+        )
+    };
+    let verify = |signatures: &[String], threshold| {
+        verify_safe_message(safe, 1, message, &signatures.concat(), &owners, threshold)
+    };
+
+    assert!(verify(&[sign(&first), sign(&second)], 2).unwrap());
+    // the same owner twice counts once
+    assert!(!verify(&[sign(&first), sign(&first)], 2).unwrap());
+    assert!(verify(&[sign(&first), sign(&first)], 1).unwrap());
+    // signatures of non-owners are ignored
+    assert!(!verify(&[sign(&first), sign(&outsider)], 2).unwrap());
+
+    assert!(verify(&[sign(&first)[..128].to_owned()], 1).is_err());
+    assert!(verify(&[sign(&first)], 0).is_err());
+}
@@ -1,7 +1,160 @@
-use ethers::types::{Address, U256};
+use crate::encoding::encode_function_call;
+use crate::types::Operation;
+use ethers::abi::Token;
+use ethers::types::{Address, NameOrAddress, TransactionRequest, U256};
 
 pub trait Transactionable: Sized + Sync + Send {
     fn calldata(&self) -> anyhow::Result<Vec<u8>>;
     fn to(&self) -> Address;
     fn value(&self) -> U256;
+
+    /// How the call is made, used by the builder and each item of a [crate::types::Bundle]
+    fn operation(&self) -> Operation {
+        Operation::CALL
+    }
+}
+
+/// A call with already encoded calldata
+#[derive(Debug, Clone)]
+pub struct RawCall {
+    pub to: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+impl Transactionable for RawCall {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.data.clone())
+    }
+
+    fn to(&self) -> Address {
+        self.to
+    }
+
+    fn value(&self) -> U256 {
+        self.value
+    }
+}
+
+impl From<(Address, U256, Vec<u8>)> for RawCall {
+    /// From a `(to, value, data)` tuple
+    fn from((to, value, data): (Address, U256, Vec<u8>)) -> Self {
+        Self { to, value, data }
+    }
+}
+
+/// `transfer(to, amount)` on an ERC20 token
+#[derive(Debug, Clone)]
+pub struct Erc20Transfer {
+    pub token: Address,
+    pub to: Address,
+    pub amount: U256,
+}
+
+impl Transactionable for Erc20Transfer {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(encode_function_call(
+            "transfer(address,uint256)",
+            &[Token::Address(self.to), Token::Uint(self.amount)],
+        ))
+    }
+
+    fn to(&self) -> Address {
+        self.token
+    }
+
+    fn value(&self) -> U256 {
+        U256::zero()
+    }
+}
+
+/// `approve(spender, amount)` on an ERC20 token
+#[derive(Debug, Clone)]
+pub struct Erc20Approve {
+    pub token: Address,
+    pub spender: Address,
+    pub amount: U256,
+}
+
+impl Transactionable for Erc20Approve {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(encode_function_call(
+            "approve(address,uint256)",
+            &[Token::Address(self.spender), Token::Uint(self.amount)],
+        ))
+    }
+
+    fn to(&self) -> Address {
+        self.token
+    }
+
+    fn value(&self) -> U256 {
+        U256::zero()
+    }
+}
+
+/// A plain transfer of the native currency, with no calldata
+#[derive(Debug, Clone)]
+pub struct EtherTransfer {
+    pub to: Address,
+    pub amount: U256,
+}
+
+impl Transactionable for EtherTransfer {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn to(&self) -> Address {
+        self.to
+    }
+
+    fn value(&self) -> U256 {
+        self.amount
+    }
+}
+
+/// Routes an ethers `TransactionRequest` through the safe, only its `to`, `value` and `data` are used
+///
+/// Built with `try_from`, which rejects ENS names and contract creations
+#[derive(Debug, Clone)]
+pub struct TxRequestWrapper(TransactionRequest);
+
+impl TxRequestWrapper {
+    pub fn into_inner(self) -> TransactionRequest {
+        self.0
+    }
+}
+
+impl TryFrom<TransactionRequest> for TxRequestWrapper {
+    type Error = anyhow::Error;
+
+    fn try_from(request: TransactionRequest) -> anyhow::Result<Self> {
+        match &request.to {
+            Some(NameOrAddress::Address(_)) => Ok(Self(request)),
+            Some(NameOrAddress::Name(name)) => {
+                anyhow::bail!("can't resolve the ENS name {name}, resolve it to an address first")
+            }
+            None => anyhow::bail!("a safe transaction can't create a contract without a `to`"),
+        }
+    }
+}
+
+impl Transactionable for TxRequestWrapper {
+    fn calldata(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.0.data.clone().unwrap_or_default().to_vec())
+    }
+
+    fn to(&self) -> Address {
+        self.0
+            .to
+            .as_ref()
+            .and_then(NameOrAddress::as_address)
+            .copied()
+            .expect("to to be checked as an address in try_from")
+    }
+
+    fn value(&self) -> U256 {
+        self.0.value.unwrap_or_default()
+    }
 }
@@ -1,5 +1,17 @@
+pub use super::admin::{FallbackHandlerKind, SafePolicy};
+pub use super::allowance::Allowance;
 pub use super::api::MultisigTransactionRequest;
-pub use super::bundle::Bundle;
-pub use super::safe::{SafeTransaction, SafeTransactionBuilder, SignedSafePayload};
-pub use super::transaction::Transactionable;
+pub use super::bundle::{Bundle, MultiSendCall};
+pub use super::chain::Chain;
+pub use super::deployment::{SafeDeployment, SafeDeploymentBuilder};
+pub use super::describe::AddressBook;
+pub use super::error::{SafeError, ServiceValidationError};
+pub use super::module::ModuleExecution;
+pub use super::safe::{
+    ExecOptions, SafeTransaction, SafeTransactionBuilder, SafeTx, SignedSafePayload, TxType,
+};
+pub use super::transaction::{
+    Erc20Approve, Erc20Transfer, EtherTransfer, RawCall, Transactionable, TxRequestWrapper,
+};
+pub use super::version::{SafeVersion, SingletonTable};
 pub use safe_client_gateway::common::models::data_decoded::Operation;
@@ -0,0 +1,131 @@
+use ethers::types::Address;
+use std::fmt;
+use std::str::FromStr;
+
+/// Canonical singletons, deployed at the same address on every chain, as (address, version)
+pub const KNOWN_SINGLETONS: &[(&str, &str)] = &[
+    ("0xb6029EA3B2c51D09a50B53CA8012FeEB05bDa35A", "1.0.0"),
+    ("0x34CfAC646f301356fAa8B21e94227e3583Fe3F5F", "1.1.1"),
+    ("0x6851D6fDFAfD08c0295C392436245E5bc78B0185", "1.2.0"),
+    ("0xd9Db270c1B5E3Bd161E8c8503c55cEABeE709552", "1.3.0"),
+    ("0x3E5c63644E683549055b9Be8653de26E0B4CD36E", "1.3.0"),
+    ("0x69f4D1788e39c87893C980c06EdF4b7f686e2938", "1.3.0"),
+    ("0xfb1bffC9d739B8D520DaF37dF666da4C687191EA", "1.3.0"),
+    ("0x41675C099F32341bf84BFc5382aF534df5C7461a", "1.4.1"),
+    ("0x29fcB43b46531BcA003ddC8FCB67FFE91900C762", "1.4.1"),
+];
+
+/// The version of a deployed safe contract, as reported by the service (e.g. "1.3.0" or "1.3.0+L2")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SafeVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl SafeVersion {
+    pub const V1_3_0: SafeVersion = SafeVersion::new(1, 3, 0);
+
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Safes before v1.3.0 leave the chainId out of their EIP-712 domain
+    pub fn domain_includes_chain_id(&self) -> bool {
+        *self >= Self::V1_3_0
+    }
+}
+
+impl FromStr for SafeVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // strip build metadata such as "+L2"
+        let core = s.split('+').next().unwrap_or_default();
+        let parts = core
+            .split('.')
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| anyhow::anyhow!("invalid safe version {s}"))?;
+
+        match parts[..] {
+            [major, minor, patch] => Ok(Self::new(major, minor, patch)),
+            [major, minor] => Ok(Self::new(major, minor, 0)),
+            _ => anyhow::bail!("invalid safe version {s}"),
+        }
+    }
+}
+
+impl fmt::Display for SafeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Maps singleton (master copy) addresses to versions, the default one knows `KNOWN_SINGLETONS`
+#[derive(Debug, Clone)]
+pub struct SingletonTable {
+    /// A chain of None matches every chain
+    singletons: Vec<(Option<u64>, Address, SafeVersion)>,
+}
+
+impl SingletonTable {
+    pub fn empty() -> Self {
+        Self { singletons: vec![] }
+    }
+
+    /// Recognize a singleton on `chain_id`, or on every chain if None
+    pub fn register(
+        mut self,
+        chain_id: Option<u64>,
+        singleton: Address,
+        version: SafeVersion,
+    ) -> Self {
+        self.singletons.push((chain_id, singleton, version));
+        self
+    }
+
+    /// Chain specific entries take precedence over canonical ones
+    pub fn lookup(&self, chain_id: u64, singleton: Address) -> Option<SafeVersion> {
+        let matching = |chain: Option<u64>| {
+            self.singletons
+                .iter()
+                .find(|(c, address, _)| *c == chain && *address == singleton)
+                .map(|(_, _, version)| *version)
+        };
+        matching(Some(chain_id)).or_else(|| matching(None))
+    }
+}
+
+impl Default for SingletonTable {
+    fn default() -> Self {
+        KNOWN_SINGLETONS
+            .iter()
+            .fold(Self::empty(), |table, (address, version)| {
+                table.register(
+                    None,
+                    address.parse().expect("known singleton address to parse"),
+                    version.parse().expect("known singleton version to parse"),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_singleton_lookup() {
+    let table = SingletonTable::default();
+    let v1_1_1: Address = "0x34CfAC646f301356fAa8B21e94227e3583Fe3F5F"
+        .parse()
+        .unwrap();
+    assert_eq!(table.lookup(1, v1_1_1), Some(SafeVersion::new(1, 1, 1)));
+    assert_eq!(table.lookup(1, Address::zero()), None);
+
+    let custom = table.register(Some(5), v1_1_1, SafeVersion::V1_3_0);
+    assert_eq!(custom.lookup(5, v1_1_1), Some(SafeVersion::V1_3_0));
+    assert_eq!(custom.lookup(1, v1_1_1), Some(SafeVersion::new(1, 1, 1)));
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "gelato")]
+
+use ethers::types::{Address, U256};
+use gnosis_safe_sdk::gelato::{gelato_task_status, TaskId, GELATO_RELAY_URL_ENV};
+use gnosis_safe_sdk::types::{Operation, RawCall, SafeTransaction};
+use mockito::Matcher;
+use serde_json::json;
+
+const SAFE: &str = "0x783c330A7A4968A08ce100A16ac27Ff2cCfAEbdf";
+const TASK_ID: &str = "0x93a87bc9d3ba9ec426e2c9d6e2e5c0bd8c1a2d6c1fbd7e4ae5b24e43b0ef7d11";
+
+// env vars are process wide, so both relay calls live in one test
+#[tokio::test]
+async fn test_relay_round_trip() {
+    let mut server = mockito::Server::new_async().await;
+    std::env::set_var(GELATO_RELAY_URL_ENV, server.url());
+
+    let tx = SafeTransaction {
+        tx: RawCall {
+            to: Address::repeat_byte(1),
+            value: U256::zero(),
+            data: vec![],
+        },
+        safe_address: SAFE.parse().unwrap(),
+        chain_id: 1,
+        safe_tx_gas: U256::zero(),
+        base_gas: U256::zero(),
+        gas_price: U256::zero(),
+        gas_token: Address::zero(),
+        refund_receiver: Address::zero(),
+        nonce: U256::zero(),
+        operation: Operation::CALL,
+        safe_version: None,
+    };
+    let signatures = "11".repeat(65);
+    let calldata = tx.exec_calldata(&signatures).unwrap();
+
+    // the relay answers a created task with 201
+    let relay_mock = server
+        .mock("POST", "/relays/v2/sponsored-call")
+        .match_body(Matcher::Json(json!({
+            "chainId": 1,
+            "target": SAFE,
+            "data": format!("0x{}", hex::encode(&calldata)),
+            "sponsorApiKey": "key",
+        })))
+        .with_status(201)
+        .with_body(json!({ "taskId": TASK_ID }).to_string())
+        .create_async()
+        .await;
+    let status_mock = server
+        .mock("GET", format!("/tasks/status/{TASK_ID}").as_str())
+        .with_status(200)
+        .with_body(
+            json!({
+                "task": {
+                    "taskId": TASK_ID,
+                    "taskState": "ExecSuccess",
+                    "transactionHash": "0x0571391c8b9dcbd1e170c8024f19986ff451e58e03ea4547b7e6090eed644e7c",
+                    "lastCheckMessage": null
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let task_id = tx.relay_gelato(&signatures, "key").await.unwrap();
+    assert_eq!(task_id, TaskId(TASK_ID.to_owned()));
+
+    let status = gelato_task_status(&task_id).await.unwrap();
+    assert_eq!(status.task_state, "ExecSuccess");
+    assert_eq!(
+        status.transaction_hash.as_deref(),
+        Some("0x0571391c8b9dcbd1e170c8024f19986ff451e58e03ea4547b7e6090eed644e7c")
+    );
+
+    relay_mock.assert_async().await;
+    status_mock.assert_async().await;
+}
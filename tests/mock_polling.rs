@@ -0,0 +1,215 @@
+use ethers::types::{Address, H256};
+use futures::StreamExt;
+use gnosis_safe_sdk::api;
+use gnosis_safe_sdk::clock::ManualClock;
+use gnosis_safe_sdk::error::SafeError;
+use serde_json::json;
+use std::time::Duration;
+
+const CHAIN_ID: u64 = 1;
+const SAFE: &str = "0x783c330A7A4968A08ce100A16ac27Ff2cCfAEbdf";
+const TX_HASH: &str = "0x0571391c8b9dcbd1e170c8024f19986ff451e58e03ea4547b7e6090eed644e7c";
+
+fn safe_tx_hash(byte: u8) -> String {
+    format!("0x{}", hex::encode([byte; 32]))
+}
+
+fn tx_info() -> serde_json::Value {
+    json!({
+        "type": "Custom",
+        "to": { "value": SAFE },
+        "dataSize": "0",
+        "value": "0",
+        "methodName": null,
+        "isCancellation": false
+    })
+}
+
+fn details(safe_tx_hash: &str, status: &str, tx_hash: Option<&str>) -> String {
+    json!({
+        "safeAddress": SAFE,
+        "txId": format!("multisig_{SAFE}_{safe_tx_hash}"),
+        "executedAt": null,
+        "txStatus": status,
+        "txInfo": tx_info(),
+        "txHash": tx_hash
+    })
+    .to_string()
+}
+
+fn queue(ids: &[&str]) -> String {
+    let results: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            json!({
+                "type": "TRANSACTION",
+                "transaction": {
+                    "id": id,
+                    "timestamp": 0,
+                    "txStatus": "AWAITING_CONFIRMATIONS",
+                    "txInfo": tx_info(),
+                    "executionInfo": null
+                },
+                "conflictType": "None"
+            })
+        })
+        .collect();
+    json!({ "next": null, "previous": null, "results": results }).to_string()
+}
+
+async fn mock_details(
+    server: &mut mockito::Server,
+    safe_tx_hash: &str,
+    status: usize,
+    body: String,
+    hits: usize,
+) -> mockito::Mock {
+    server
+        .mock(
+            "GET",
+            format!("/{CHAIN_ID}/transactions/{safe_tx_hash}").as_str(),
+        )
+        .with_status(status)
+        .with_body(body)
+        .expect(hits)
+        .create_async()
+        .await
+}
+
+// env vars are process wide, so every helper is driven from one test
+#[tokio::test]
+async fn test_polling_with_manual_clock() {
+    let mut server = mockito::Server::new_async().await;
+    std::env::set_var(api::BASE_URL_ENV, server.url());
+    let timeout = Duration::from_secs(10);
+    let interval = Duration::from_secs(1);
+
+    // polls at 0, 1, 3 and 7 seconds, the next one at 15 would be past the deadline
+    let awaiting = safe_tx_hash(1);
+    let awaiting_mock = mock_details(
+        &mut server,
+        &awaiting,
+        200,
+        details(&awaiting, "AWAITING_CONFIRMATIONS", None),
+        4,
+    )
+    .await;
+    let clock = ManualClock::new();
+    let error =
+        api::wait_for_confirmations_with_clock(CHAIN_ID, &awaiting, timeout, interval, &clock)
+            .await
+            .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SafeError>(),
+        Some(SafeError::Timeout(t)) if *t == timeout
+    ));
+    assert_eq!(clock.elapsed(), Duration::from_secs(7));
+    awaiting_mock.assert_async().await;
+
+    // a wrong hash fails at once rather than at the deadline
+    let unknown = safe_tx_hash(2);
+    let unknown_mock = mock_details(&mut server, &unknown, 404, "{}".to_owned(), 1).await;
+    let clock = ManualClock::new();
+    let error = api::wait_for_execution_with_clock(CHAIN_ID, &unknown, timeout, interval, &clock)
+        .await
+        .unwrap_err();
+    assert!(error.downcast_ref::<SafeError>().is_none());
+    assert_eq!(clock.elapsed(), Duration::ZERO);
+    unknown_mock.assert_async().await;
+
+    // an unavailable service is retried
+    let executed = safe_tx_hash(3);
+    let unavailable_mock = mock_details(&mut server, &executed, 503, "{}".to_owned(), 1).await;
+    let executed_mock = mock_details(
+        &mut server,
+        &executed,
+        200,
+        details(&executed, "SUCCESS", Some(TX_HASH)),
+        1,
+    )
+    .await;
+    let clock = ManualClock::new();
+    let tx_hash =
+        api::wait_for_execution_with_clock(CHAIN_ID, &executed, timeout, interval, &clock)
+            .await
+            .unwrap();
+    assert_eq!(tx_hash, TX_HASH.parse::<H256>().unwrap());
+    assert_eq!(clock.elapsed(), interval);
+    unavailable_mock.assert_async().await;
+    executed_mock.assert_async().await;
+
+    let failed = safe_tx_hash(4);
+    let failed_mock = mock_details(
+        &mut server,
+        &failed,
+        200,
+        details(&failed, "FAILED", Some(TX_HASH)),
+        1,
+    )
+    .await;
+    let error = api::wait_for_execution_with_clock(
+        CHAIN_ID,
+        &failed,
+        timeout,
+        interval,
+        &ManualClock::new(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SafeError>(),
+        Some(SafeError::ExecutionFailed(tx_hash)) if *tx_hash == TX_HASH.parse::<H256>().unwrap()
+    ));
+    failed_mock.assert_async().await;
+
+    let cancelled = safe_tx_hash(5);
+    let cancelled_mock = mock_details(
+        &mut server,
+        &cancelled,
+        200,
+        details(&cancelled, "CANCELLED", None),
+        1,
+    )
+    .await;
+    let error = api::wait_for_execution_with_clock(
+        CHAIN_ID,
+        &cancelled,
+        timeout,
+        interval,
+        &ManualClock::new(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<SafeError>(),
+        Some(SafeError::ExecutionCancelled)
+    ));
+    cancelled_mock.assert_async().await;
+
+    // the second poll yields only the transaction it didn't see in the first
+    let queued_path = format!("/{CHAIN_ID}/safes/{SAFE}/transactions/queued");
+    let first_poll = server
+        .mock("GET", queued_path.as_str())
+        .with_status(200)
+        .with_body(queue(&["a", "b"]))
+        .expect(1)
+        .create_async()
+        .await;
+    let second_poll = server
+        .mock("GET", queued_path.as_str())
+        .with_status(200)
+        .with_body(queue(&["a", "b", "c"]))
+        .expect(1)
+        .create_async()
+        .await;
+    let safe: Address = SAFE.parse().unwrap();
+    let ids: Vec<String> =
+        api::watch_pending_with_clock(CHAIN_ID, safe, interval, ManualClock::new())
+            .take(3)
+            .map(|tx| tx.unwrap().id)
+            .collect()
+            .await;
+    assert_eq!(ids, ["a", "b", "c"]);
+    first_poll.assert_async().await;
+    second_poll.assert_async().await;
+}
@@ -0,0 +1,186 @@
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, U256};
+use gnosis_safe_sdk::api;
+use gnosis_safe_sdk::types::{EtherTransfer, SafeTransactionBuilder};
+use mockito::Matcher;
+use serde_json::json;
+
+const CHAIN_ID: u64 = 1;
+const SAFE: &str = "0x783c330A7A4968A08ce100A16ac27Ff2cCfAEbdf";
+// anvil's first development key
+const OWNER_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const OWNER: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+// the 1 ether transfer to OWNER at nonce 7, hashed and signed outside of this crate
+const SAFE_TX_HASH: &str = "0x0571391c8b9dcbd1e170c8024f19986ff451e58e03ea4547b7e6090eed644e7c";
+const SIGNATURE: &str = "0x3a25737920a88ff0065965d024fc4a213d8af75f40b63ed6e0ed0985647b72d92d7fbea18065f92375ba0abc32ac94f115f7ca5dd287c2c6be9beace20b315191b";
+
+fn safe_info() -> serde_json::Value {
+    json!({
+        "address": { "value": SAFE },
+        "chainId": CHAIN_ID.to_string(),
+        "nonce": 7,
+        "threshold": 1,
+        "owners": [{ "value": OWNER }],
+        "implementation": { "value": "0xd9Db270c1B5E3Bd161E8c8503c55cEABeE709552" },
+        "implementationVersionState": "UP_TO_DATE",
+        "modules": null,
+        "fallbackHandler": { "value": "0xf48f2B2d2a534e402487b3ee7C18c33Aec0Fe5e4" },
+        "guard": null,
+        "version": "1.3.0",
+        "collectiblesTag": "0",
+        "txQueuedTag": "0",
+        "txHistoryTag": "0"
+    })
+}
+
+fn proposed_details() -> serde_json::Value {
+    json!({
+        "safeAddress": SAFE,
+        "txId": format!("multisig_{SAFE}_{SAFE_TX_HASH}"),
+        "executedAt": null,
+        "txStatus": "AWAITING_EXECUTION",
+        "txInfo": {
+            "type": "Custom",
+            "to": { "value": OWNER },
+            "dataSize": "0",
+            "value": "1000000000000000000",
+            "methodName": null,
+            "isCancellation": false
+        },
+        "txHash": null
+    })
+}
+
+/// The proposal as the service reads it back, storing `value` as the amount sent
+fn stored_details(value: &str) -> serde_json::Value {
+    let zero = "0x0000000000000000000000000000000000000000";
+    let mut details = proposed_details();
+    details["txData"] = json!({
+        "hexData": null,
+        "dataDecoded": null,
+        "to": { "value": OWNER },
+        "value": value,
+        "operation": 0
+    });
+    details["detailedExecutionInfo"] = json!({
+        "type": "MULTISIG",
+        "submittedAt": 1700000000000i64,
+        "nonce": 7,
+        "safeTxGas": "0",
+        "baseGas": "0",
+        "gasPrice": "0",
+        "gasToken": zero,
+        "refundReceiver": { "value": zero },
+        "safeTxHash": SAFE_TX_HASH,
+        "executor": null,
+        "signers": [{ "value": OWNER }],
+        "confirmationsRequired": 1,
+        "confirmations": [{
+            "signer": { "value": OWNER },
+            "signature": SIGNATURE,
+            "submittedAt": 1700000000000i64
+        }],
+        "rejectors": [],
+        "trusted": true
+    });
+    details
+}
+
+// env vars are process wide, so the whole round trip lives in one test
+#[tokio::test]
+async fn test_propose_round_trip() {
+    let mut server = mockito::Server::new_async().await;
+    std::env::set_var(api::BASE_URL_ENV, server.url());
+
+    let safe_mock = server
+        .mock("GET", format!("/{CHAIN_ID}/safes/{SAFE}").as_str())
+        .with_status(200)
+        .with_body(safe_info().to_string())
+        .create_async()
+        .await;
+    let queued_mock = server
+        .mock(
+            "GET",
+            format!("/{CHAIN_ID}/safes/{SAFE}/transactions/queued").as_str(),
+        )
+        .with_status(200)
+        .with_body(json!({ "next": null, "previous": null, "results": [] }).to_string())
+        .create_async()
+        .await;
+
+    let safe: Address = SAFE.parse().unwrap();
+    let wallet: LocalWallet = OWNER_KEY.parse().unwrap();
+
+    assert!(api::queued(CHAIN_ID, safe).await.unwrap().is_empty());
+
+    let tx = SafeTransactionBuilder::new(
+        EtherTransfer {
+            to: OWNER.parse().unwrap(),
+            amount: U256::exp10(18),
+        },
+        CHAIN_ID,
+        safe,
+    )
+    .build()
+    .await
+    .unwrap();
+    assert_eq!(tx.nonce, U256::from(7));
+
+    let signed = tx.sign_safe_tx(&wallet).await.unwrap();
+    assert_eq!(signed.sender, OWNER.parse::<Address>().unwrap());
+
+    let propose_mock = server
+        .mock(
+            "POST",
+            format!("/{CHAIN_ID}/transactions/{SAFE}/propose").as_str(),
+        )
+        .match_body(Matcher::Json(json!({
+            "to": OWNER,
+            "value": "1000000000000000000",
+            "data": "0x",
+            "nonce": "7",
+            "operation": 0,
+            "safeTxGas": "0",
+            "baseGas": "0",
+            "gasPrice": "0",
+            "gasToken": "0x0000000000000000000000000000000000000000",
+            "refundReceiver": "0x0000000000000000000000000000000000000000",
+            "safeTxHash": SAFE_TX_HASH,
+            "sender": OWNER,
+            "signature": SIGNATURE,
+        })))
+        .with_status(200)
+        .with_body(proposed_details().to_string())
+        .create_async()
+        .await;
+
+    let details = api::propose(signed.clone()).await.unwrap();
+    assert_eq!(details.tx_id, format!("multisig_{SAFE}_{SAFE_TX_HASH}"));
+
+    // read back as signed, then with the value changed behind the owners' backs
+    let details_path = format!("/{CHAIN_ID}/transactions/{SAFE_TX_HASH}");
+    let stored_mock = server
+        .mock("GET", details_path.as_str())
+        .with_status(200)
+        .with_body(stored_details("1000000000000000000").to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let tampered_mock = server
+        .mock("GET", details_path.as_str())
+        .with_status(200)
+        .with_body(stored_details("2000000000000000000").to_string())
+        .expect(1)
+        .create_async()
+        .await;
+
+    api::verify_proposal(&signed).await.unwrap();
+    let error = api::verify_proposal(&signed).await.unwrap_err();
+    assert!(error.to_string().ends_with("with a different value"));
+
+    safe_mock.assert_async().await;
+    queued_mock.assert_async().await;
+    propose_mock.assert_async().await;
+    stored_mock.assert_async().await;
+    tampered_mock.assert_async().await;
+}